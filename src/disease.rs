@@ -0,0 +1,233 @@
+use crate::species::Species;
+use crate::traits::Organismo;
+use crate::util::{probabilidad, validar_no_negativo, validar_probabilidad};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum EstadoSalud {
+    Susceptible,
+    Incubando { dias_restantes: u32 },
+    Enfermo,
+    // `None` es inmunidad para toda la vida.
+    Inmune { dias_restantes: Option<u32> },
+}
+
+// Resultado de un día de enfermedad para un individuo.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Progreso {
+    SinCambios,
+    Contagiado,
+    Enfermo,
+    Recuperado,
+    PerdioInmunidad,
+    Murio,
+}
+
+// Cómo se comporta una enfermedad en una especie huésped.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ParametrosHuesped {
+    // Probabilidad diaria de contagio espontáneo, sin contacto con infecciosos.
+    pub prob_enfermar: f64,
+    pub prob_muerte_enfermedad: f64,
+    #[serde(default = "dias_incubacion_por_defecto")]
+    pub dias_incubacion: u32,
+    #[serde(default = "prob_recuperacion_por_defecto")]
+    pub prob_recuperacion: f64,
+    // Omitirlo significa inmunidad de por vida.
+    #[serde(default)]
+    pub dias_inmunidad: Option<u32>,
+}
+
+fn dias_incubacion_por_defecto() -> u32 {
+    5
+}
+
+fn prob_recuperacion_por_defecto() -> f64 {
+    0.05
+}
+
+impl ParametrosHuesped {
+    // `riesgo` multiplica la probabilidad de contagiarse: 1 para un animal en buena condición.
+    pub fn avanzar(
+        &self,
+        estado: &mut EstadoSalud,
+        presion_contagio: f64,
+        riesgo: f64,
+        rng: &mut dyn RngCore,
+    ) -> Progreso {
+        match *estado {
+            EstadoSalud::Susceptible => {
+                let prob_contagio =
+                    ((1.0 - (1.0 - self.prob_enfermar) * (1.0 - presion_contagio)) * riesgo).clamp(0.0, 1.0);
+                if !probabilidad(prob_contagio, rng) {
+                    return Progreso::SinCambios;
+                }
+                if self.dias_incubacion == 0 {
+                    *estado = EstadoSalud::Enfermo;
+                    Progreso::Enfermo
+                } else {
+                    *estado = EstadoSalud::Incubando { dias_restantes: self.dias_incubacion };
+                    Progreso::Contagiado
+                }
+            }
+            EstadoSalud::Incubando { dias_restantes } => {
+                if dias_restantes <= 1 {
+                    *estado = EstadoSalud::Enfermo;
+                    Progreso::Enfermo
+                } else {
+                    *estado = EstadoSalud::Incubando { dias_restantes: dias_restantes - 1 };
+                    Progreso::SinCambios
+                }
+            }
+            EstadoSalud::Enfermo => {
+                if probabilidad(self.prob_muerte_enfermedad, rng) {
+                    Progreso::Murio
+                } else if probabilidad(self.prob_recuperacion, rng) {
+                    *estado = EstadoSalud::Inmune { dias_restantes: self.dias_inmunidad };
+                    Progreso::Recuperado
+                } else {
+                    Progreso::SinCambios
+                }
+            }
+            EstadoSalud::Inmune { dias_restantes: None } => Progreso::SinCambios,
+            EstadoSalud::Inmune { dias_restantes: Some(dias) } => {
+                if dias <= 1 {
+                    *estado = EstadoSalud::Susceptible;
+                    Progreso::PerdioInmunidad
+                } else {
+                    *estado = EstadoSalud::Inmune { dias_restantes: Some(dias - 1) };
+                    Progreso::SinCambios
+                }
+            }
+        }
+    }
+}
+
+// Transmisión entre individuos. Con `beta = 0` solo queda el contagio espontáneo de `prob_enfermar`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ConfigContagio {
+    pub beta: f64,
+    // Si es true, solo contagian los de la misma especie.
+    pub por_especie: bool,
+    // Si es true, solo contagian los del mismo grupo de contacto.
+    pub por_grupo: bool,
+    // Con true la presión es beta * I / N; si no, cada infeccioso contagia con probabilidad beta.
+    pub dependiente_de_frecuencia: bool,
+}
+
+impl Default for ConfigContagio {
+    fn default() -> Self {
+        ConfigContagio {
+            beta: 0.0,
+            por_especie: true,
+            por_grupo: false,
+            dependiente_de_frecuencia: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Enfermedad {
+    pub nombre: String,
+    pub huespedes: BTreeMap<Species, ParametrosHuesped>,
+    #[serde(default)]
+    pub contagio: ConfigContagio,
+}
+
+type ClaveContacto = (Option<Species>, Option<u32>);
+
+// Infecciosos y total de huéspedes en cada grupo de contacto, tomados al empezar el día.
+pub struct Contactos {
+    conteo: BTreeMap<ClaveContacto, (usize, usize)>,
+}
+
+impl Enfermedad {
+    pub fn validar(&self) -> Result<(), String> {
+        validar_no_negativo(&format!("contagio.beta de {}", self.nombre), self.contagio.beta)?;
+        for (species, h) in &self.huespedes {
+            let campo = |nombre: &str| format!("{} de {} en {}", nombre, self.nombre, species.clave());
+            validar_probabilidad(&campo("prob_enfermar"), h.prob_enfermar)?;
+            validar_probabilidad(&campo("prob_muerte_enfermedad"), h.prob_muerte_enfermedad)?;
+            validar_probabilidad(&campo("prob_recuperacion"), h.prob_recuperacion)?;
+        }
+        Ok(())
+    }
+
+    pub fn huesped(&self, species: &Species) -> Option<&ParametrosHuesped> {
+        self.huespedes.get(species)
+    }
+
+    fn clave(&self, a: &dyn Organismo) -> ClaveContacto {
+        (
+            if self.contagio.por_especie { a.especie() } else { None },
+            if self.contagio.por_grupo { a.grupo_contacto() } else { None },
+        )
+    }
+
+    pub fn contar(&self, idx: usize, animals: &[Box<dyn Organismo>]) -> Contactos {
+        let mut conteo: BTreeMap<ClaveContacto, (usize, usize)> = BTreeMap::new();
+        for a in animals {
+            // Los aislados no cuentan: no están en contacto con el resto.
+            if a.aislado() || !a.especie().is_some_and(|s| self.huespedes.contains_key(&s)) {
+                continue;
+            }
+            let entrada = conteo.entry(self.clave(a.as_ref())).or_insert((0, 0));
+            if a.contagia(idx) {
+                entrada.0 += 1;
+            }
+            entrada.1 += 1;
+        }
+        Contactos { conteo }
+    }
+
+    // Probabilidad de contagiarse hoy por contacto con los infecciosos de su grupo.
+    pub fn presion(&self, contactos: &Contactos, a: &dyn Organismo) -> f64 {
+        let Some(&(infecciosos, total)) = contactos.conteo.get(&self.clave(a)) else {
+            return 0.0;
+        };
+        let beta = self.contagio.beta;
+        if beta <= 0.0 || infecciosos == 0 {
+            return 0.0;
+        }
+        if self.contagio.dependiente_de_frecuencia {
+            1.0 - (-beta * infecciosos as f64 / total as f64).exp()
+        } else {
+            1.0 - (1.0 - beta.min(1.0)).powi(infecciosos as i32)
+        }
+    }
+}
+
+// Las enfermedades de una corrida; cada individuo guarda su estado por índice de enfermedad.
+#[derive(Debug, Clone, Default)]
+pub struct RegistroEnfermedades {
+    enfermedades: Vec<Enfermedad>,
+}
+
+impl RegistroEnfermedades {
+    pub fn new(enfermedades: Vec<Enfermedad>) -> Self {
+        RegistroEnfermedades { enfermedades }
+    }
+
+    pub fn len(&self) -> usize {
+        self.enfermedades.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.enfermedades.is_empty()
+    }
+
+    pub fn get(&self, idx: usize) -> Option<&Enfermedad> {
+        self.enfermedades.get(idx)
+    }
+
+    pub fn buscar(&self, nombre: &str) -> Option<usize> {
+        self.enfermedades.iter().position(|e| e.nombre == nombre)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &Enfermedad)> {
+        self.enfermedades.iter().enumerate()
+    }
+}
@@ -0,0 +1,185 @@
+use crate::genealogy::CausaMuerte;
+use crate::history::DayRecord;
+use crate::species::Species;
+use serde::Serialize;
+
+// Lo que pasó durante un día, en el orden en que pasó.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum Evento {
+    Mated { female_id: u32, male_id: u32, species: Species },
+    MateNotFound { prey_id: u32, species: Species },
+    Birth { prey_id: u32, mother_id: u32, father_id: Option<u32>, species: Species },
+    Infected { prey_id: u32, species: Species, disease: String },
+    FellSick { prey_id: u32, species: Species, disease: String },
+    Recovered { prey_id: u32, species: Species, disease: String },
+    ImmunityWaned { prey_id: u32, species: Species, disease: String },
+    DiedOfDisease { prey_id: u32, species: Species, disease: String },
+    Vaccinated { prey_id: u32, species: Species, disease: String, protected: bool },
+    Treated { prey_id: u32, species: Species, disease: String, cured: bool },
+    Isolated { prey_id: u32, species: Species },
+    ReleasedFromQuarantine { prey_id: u32, species: Species },
+    DiedOfOldAge { prey_id: u32, species: Species, age_days: u32 },
+    StarvedToDeath { prey_id: u32, species: Species, days_hungry: u32 },
+    Hunted { predator_id: u32, prey_id: u32, species: Species, weight: f64, eaten: f64, stored: f64, probability: f64 },
+    HuntFailed { predator_id: u32, prey_id: u32, species: Species, probability: f64 },
+    ReserveDrawn { predator_id: u32, species: String, amount: f64, remaining: f64 },
+    PredatorStarved { predator_id: u32, species: String, available: f64, minimum: f64, days_starving: u32 },
+    PredatorBorn { predator_id: u32, parent_id: u32, species: String },
+    PredatorDied { predator_id: u32, species: String, cause: CausaMuerte, age_days: u32 },
+    PredatorReplaced { predator_id: u32, species: String },
+}
+
+// Recibe, al cerrar cada día, el registro del día y sus eventos.
+pub trait Suscriptor {
+    fn dia_simulado(&mut self, registro: &DayRecord, eventos: &[Evento]);
+}
+
+impl<F: FnMut(&DayRecord, &[Evento])> Suscriptor for F {
+    fn dia_simulado(&mut self, registro: &DayRecord, eventos: &[Evento]) {
+        self(registro, eventos)
+    }
+}
+
+// El suscriptor por defecto de los binarios: el mismo registro por consola de siempre.
+pub struct LogSuscriptor;
+
+impl LogSuscriptor {
+    fn describir(evento: &Evento) -> String {
+        match evento {
+            Evento::Mated { female_id, male_id, species } => format!(
+                "{} #{} se apareó con el macho #{} y quedó preñada.",
+                species.nombre(), female_id, male_id
+            ),
+            Evento::MateNotFound { prey_id, species } => format!(
+                "{} #{} está en celo pero no hay macho disponible.",
+                species.nombre(), prey_id
+            ),
+            Evento::Birth { prey_id, mother_id, father_id, species } => match father_id {
+                Some(father_id) => format!(
+                    "Nació {} #{} (madre #{}, padre #{}).",
+                    species.nombre(), prey_id, mother_id, father_id
+                ),
+                None => format!("Nació {} #{} (madre #{}).", species.nombre(), prey_id, mother_id),
+            },
+            Evento::Infected { prey_id, species, disease } => {
+                format!("{} #{} se contagió de {} (en incubación).", species.nombre(), prey_id, disease)
+            }
+            Evento::FellSick { prey_id, species, disease } => {
+                format!("{} #{} enfermó de {}.", species.nombre(), prey_id, disease)
+            }
+            Evento::Recovered { prey_id, species, disease } => {
+                format!("{} #{} se recuperó de {} y quedó inmune.", species.nombre(), prey_id, disease)
+            }
+            Evento::ImmunityWaned { prey_id, species, disease } => {
+                format!("{} #{} perdió la inmunidad a {}.", species.nombre(), prey_id, disease)
+            }
+            Evento::DiedOfDisease { prey_id, species, disease } => {
+                format!("Un individuo de {} (#{}) murió por {}.", species.nombre(), prey_id, disease)
+            }
+            Evento::Vaccinated { prey_id, species, disease, protected } => {
+                if *protected {
+                    format!("{} #{} fue vacunada contra {}.", species.nombre(), prey_id, disease)
+                } else {
+                    format!("{} #{} recibió la vacuna contra {} sin efecto.", species.nombre(), prey_id, disease)
+                }
+            }
+            Evento::Treated { prey_id, species, disease, cured } => {
+                if *cured {
+                    format!("{} #{} fue tratada por {} y se curó.", species.nombre(), prey_id, disease)
+                } else {
+                    format!("{} #{} fue tratada por {} y sigue enferma.", species.nombre(), prey_id, disease)
+                }
+            }
+            Evento::Isolated { prey_id, species } => {
+                format!("{} #{} entró en cuarentena.", species.nombre(), prey_id)
+            }
+            Evento::ReleasedFromQuarantine { prey_id, species } => {
+                format!("{} #{} salió de cuarentena.", species.nombre(), prey_id)
+            }
+            Evento::DiedOfOldAge { prey_id, species, age_days } => {
+                format!("{} #{} murió de vieja a los {} días.", species.nombre(), prey_id, age_days)
+            }
+            Evento::StarvedToDeath { prey_id, species, days_hungry } => format!(
+                "{} #{} murió de hambre tras {} días adelgazando.",
+                species.nombre(), prey_id, days_hungry
+            ),
+            Evento::Hunted { predator_id, prey_id, species, weight, eaten, stored, .. } => {
+                if *stored > 0.0 {
+                    format!(
+                        "Depredador #{} cazó {} #{} de {:.2}, consumió {:.2} y guardó {:.2} en reserva.",
+                        predator_id, species.nombre(), prey_id, weight, eaten, stored
+                    )
+                } else {
+                    format!(
+                        "Depredador #{} cazó presa completa: {} #{} ({:.2}).",
+                        predator_id, species.nombre(), prey_id, weight
+                    )
+                }
+            }
+            Evento::HuntFailed { predator_id, prey_id, species, probability } => format!(
+                "Depredador #{} intentó cazar {} #{} y se le escapó (probabilidad de éxito {:.0}%).",
+                predator_id, species.nombre(), prey_id, probability * 100.0
+            ),
+            Evento::ReserveDrawn { predator_id, species, amount, remaining } => format!(
+                "{} #{} usó {:.2} de reserva para alcanzar el mínimo. Reserva restante: {:.2}",
+                species, predator_id, amount, remaining
+            ),
+            Evento::PredatorStarved { predator_id, species, available, minimum, days_starving } => format!(
+                "{} #{} no alcanzó el mínimo diario ({:.2} < {:.2}), {} días seguidos con hambre.",
+                species, predator_id, available, minimum, days_starving
+            ),
+            Evento::PredatorBorn { predator_id, parent_id, species } => {
+                format!("Nació {} #{} (cría de #{}).", species, predator_id, parent_id)
+            }
+            Evento::PredatorDied { predator_id, species, cause, age_days } => {
+                format!("{} #{} murió de {} a los {} días.", species, predator_id, cause.nombre(), age_days)
+            }
+            Evento::PredatorReplaced { predator_id, species } => {
+                format!("Llegó un {} nuevo (#{}).", species, predator_id)
+            }
+        }
+    }
+}
+
+impl Suscriptor for LogSuscriptor {
+    fn dia_simulado(&mut self, registro: &DayRecord, eventos: &[Evento]) {
+        println!("\n--- Día {} ---", registro.dia);
+        for evento in eventos {
+            println!("{}", Self::describir(evento));
+        }
+
+        let mut conteo: Vec<(&str, usize)> = registro
+            .especies
+            .iter()
+            .map(|c| (c.species.nombre(), c.machos + c.hembras))
+            .filter(|(_, cantidad)| *cantidad > 0)
+            .collect();
+        conteo.sort();
+        println!("Población actual: {} animales", conteo.iter().map(|(_, c)| c).sum::<usize>());
+        for (nombre, cantidad) in conteo {
+            println!("{}: {}", nombre, cantidad);
+        }
+
+        if let Some(pasto) = registro.pasto {
+            println!(
+                "Pasto: {:.2} kg, comido hoy: {:.2} de {:.2} pedidos",
+                pasto,
+                registro.especies.iter().map(|c| c.forraje_comido).sum::<f64>(),
+                registro.especies.iter().map(|c| c.forraje_demandado).sum::<f64>()
+            );
+        }
+        for c in &registro.depredadores {
+            println!(
+                "{}: {} vivos, reserva acumulada: {:.2}, consumido hoy: {:.2}",
+                c.especie, c.vivos, c.reserva, c.consumido_hoy
+            );
+        }
+        let costos = &registro.costos_veterinarios;
+        if costos.total() > 0.0 {
+            println!(
+                "Costo veterinario del día: {:.2} (vacunas {:.2}, tratamientos {:.2}, cuarentena {:.2}), aislados: {}",
+                costos.total(), costos.vacunacion, costos.tratamiento, costos.cuarentena, registro.aislados
+            );
+        }
+    }
+}
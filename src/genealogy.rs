@@ -0,0 +1,183 @@
+use crate::events::Evento;
+use crate::organism::prey::Sex;
+use crate::species::Species;
+use crate::traits::Organismo;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{self, Write};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum CausaMuerte {
+    Enfermedad(String),
+    Vejez,
+    Cazado,
+    Hambre,
+}
+
+impl CausaMuerte {
+    pub fn nombre(&self) -> &str {
+        match self {
+            CausaMuerte::Enfermedad(nombre) => nombre,
+            CausaMuerte::Vejez => "vejez",
+            CausaMuerte::Cazado => "cazado",
+            CausaMuerte::Hambre => "hambre",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Ficha {
+    pub id: u32,
+    pub species: Species,
+    pub sex: Sex,
+    pub madre: Option<u32>,
+    pub padre: Option<u32>,
+    pub dia_nacimiento: u32,
+    pub dia_muerte: Option<u32>,
+    pub causa_muerte: Option<CausaMuerte>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Arista {
+    pub desde: u32,
+    pub hasta: u32,
+    pub parentesco: &'static str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PedigriEspecie<'a> {
+    pub species: Species,
+    pub nodos: Vec<&'a Ficha>,
+    pub aristas: Vec<Arista>,
+}
+
+// Todos los individuos que pasaron por la corrida, vivos o muertos, indexados por id.
+#[derive(Debug, Clone, Default)]
+pub struct Genealogia {
+    fichas: BTreeMap<u32, Ficha>,
+}
+
+impl Genealogia {
+    pub fn registrar(&mut self, individuo: &dyn Organismo) {
+        let (Some(id), Some(species), Some(es_macho)) =
+            (individuo.id(), individuo.especie(), individuo.es_macho())
+        else {
+            return;
+        };
+        self.fichas.insert(
+            id,
+            Ficha {
+                id,
+                species,
+                sex: if es_macho { Sex::Male } else { Sex::Female },
+                madre: individuo.madre(),
+                padre: individuo.padre(),
+                dia_nacimiento: individuo.dia_nacimiento().unwrap_or(0),
+                dia_muerte: None,
+                causa_muerte: None,
+            },
+        );
+    }
+
+    pub fn procesar_eventos(&mut self, dia: u32, eventos: &[Evento]) {
+        for evento in eventos {
+            let (id, causa) = match evento {
+                Evento::DiedOfDisease { prey_id, disease, .. } => {
+                    (*prey_id, CausaMuerte::Enfermedad(disease.clone()))
+                }
+                Evento::DiedOfOldAge { prey_id, .. } => (*prey_id, CausaMuerte::Vejez),
+                Evento::StarvedToDeath { prey_id, .. } => (*prey_id, CausaMuerte::Hambre),
+                Evento::Hunted { prey_id, .. } => (*prey_id, CausaMuerte::Cazado),
+                _ => continue,
+            };
+            if let Some(ficha) = self.fichas.get_mut(&id) {
+                ficha.dia_muerte = Some(dia);
+                ficha.causa_muerte = Some(causa);
+            }
+        }
+    }
+
+    pub fn get(&self, id: u32) -> Option<&Ficha> {
+        self.fichas.get(&id)
+    }
+
+    pub fn fichas(&self) -> impl Iterator<Item = &Ficha> {
+        self.fichas.values()
+    }
+
+    // Cada descendiente una sola vez, aunque descienda por los dos lados: con un macho que cubre a
+    // todo el rebaño, la endogamia es lo normal.
+    pub fn descendientes(&self, id: u32) -> Vec<&Ficha> {
+        let mut crias: BTreeMap<u32, Vec<&Ficha>> = BTreeMap::new();
+        for f in self.fichas.values() {
+            for progenitor in f.madre.iter().chain(f.padre.iter()) {
+                crias.entry(*progenitor).or_default().push(f);
+            }
+        }
+        let mut vistos: BTreeSet<u32> = BTreeSet::new();
+        let mut resultado = Vec::new();
+        let mut pendientes = vec![id];
+        while let Some(actual) = pendientes.pop() {
+            for &f in crias.get(&actual).into_iter().flatten() {
+                if vistos.insert(f.id) {
+                    resultado.push(f);
+                    pendientes.push(f.id);
+                }
+            }
+        }
+        resultado.sort_by_key(|f| f.id);
+        resultado
+    }
+
+    pub fn por_especie(&self) -> Vec<PedigriEspecie<'_>> {
+        let especies: BTreeSet<&Species> = self.fichas.values().map(|f| &f.species).collect();
+        especies
+            .into_iter()
+            .map(|species| {
+                let nodos: Vec<&Ficha> = self.fichas.values().filter(|f| &f.species == species).collect();
+                let mut aristas = Vec::new();
+                for f in &nodos {
+                    if let Some(madre) = f.madre {
+                        aristas.push(Arista { desde: madre, hasta: f.id, parentesco: "madre" });
+                    }
+                    if let Some(padre) = f.padre {
+                        aristas.push(Arista { desde: padre, hasta: f.id, parentesco: "padre" });
+                    }
+                }
+                PedigriEspecie { species: species.clone(), nodos, aristas }
+            })
+            .collect()
+    }
+
+    pub fn escribir_json(&self, w: impl Write) -> io::Result<()> {
+        serde_json::to_writer_pretty(w, &self.por_especie()).map_err(io::Error::from)
+    }
+
+    // Un subgrafo por especie; las aristas van de progenitor a cría.
+    pub fn escribir_dot(&self, mut w: impl Write) -> io::Result<()> {
+        writeln!(w, "digraph genealogia {{")?;
+        writeln!(w, "    node [shape=box, fontsize=10];")?;
+        for pedigri in self.por_especie() {
+            writeln!(w, "    subgraph \"cluster_{}\" {{", pedigri.species.clave())?;
+            writeln!(w, "        label=\"{}\";", pedigri.species.nombre())?;
+            for f in &pedigri.nodos {
+                let sexo = if f.sex == Sex::Male { "M" } else { "H" };
+                let muerte = match (f.dia_muerte, &f.causa_muerte) {
+                    (Some(dia), Some(causa)) => format!("\\nmuere {} ({})", dia, causa.nombre()),
+                    _ => String::new(),
+                };
+                writeln!(
+                    w,
+                    "        n{} [label=\"#{} {}\\nnace {}{}\"];",
+                    f.id, f.id, sexo, f.dia_nacimiento, muerte
+                )?;
+            }
+            for a in &pedigri.aristas {
+                let estilo = if a.parentesco == "padre" { " [style=dashed]" } else { "" };
+                writeln!(w, "        n{} -> n{}{};", a.desde, a.hasta, estilo)?;
+            }
+            writeln!(w, "    }}")?;
+        }
+        writeln!(w, "}}")
+    }
+}
@@ -0,0 +1,341 @@
+use crate::organism::prey::Sex;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::rc::Rc;
+
+// Peso máximo que puede tener un animal a cada edad, en kg. Lo que pesa de verdad depende además
+// de lo que come.
+pub trait GrowthModel: fmt::Debug {
+    fn peso(&self, edad: u32) -> f64;
+}
+
+// a·exp(−b·exp(−k·t))
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct Gompertz {
+    pub a: f64,
+    pub b: f64,
+    pub k: f64,
+}
+
+impl GrowthModel for Gompertz {
+    fn peso(&self, edad: u32) -> f64 {
+        self.a * (-self.b * (-self.k * edad as f64).exp()).exp()
+    }
+}
+
+// a·(1 − b·exp(−k·t))³
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct VonBertalanffy {
+    pub a: f64,
+    pub b: f64,
+    pub k: f64,
+}
+
+impl GrowthModel for VonBertalanffy {
+    fn peso(&self, edad: u32) -> f64 {
+        self.a * (1.0 - self.b * (-self.k * edad as f64).exp()).max(0.0).powi(3)
+    }
+}
+
+// a / (1 + b·exp(−k·t))
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct Logistico {
+    pub a: f64,
+    pub b: f64,
+    pub k: f64,
+}
+
+impl GrowthModel for Logistico {
+    fn peso(&self, edad: u32) -> f64 {
+        self.a / (1.0 + self.b * (-self.k * edad as f64).exp())
+    }
+}
+
+// a·(1 − b·exp(−k·t))^m: con m = 3 es von Bertalanffy. Con b fijo y m grande la curva se va a 0;
+// se acerca a Gompertz a·exp(−c·exp(−k·t)) solo si b = c/m.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct Richards {
+    pub a: f64,
+    pub b: f64,
+    pub k: f64,
+    pub m: f64,
+}
+
+impl GrowthModel for Richards {
+    fn peso(&self, edad: u32) -> f64 {
+        self.a * (1.0 - self.b * (-self.k * edad as f64).exp()).max(0.0).powf(self.m)
+    }
+}
+
+// Las familias de curvas que se pueden ajustar a pesajes.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Familia {
+    Gompertz,
+    VonBertalanffy,
+    Logistico,
+    Richards,
+}
+
+// Las curvas que se pueden elegir desde el escenario: con parámetros dados o ajustadas a pesajes,
+// pares `[edad en días, peso en kg]` de la especie (y del sexo, si la curva es por sexo).
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "tipo", rename_all = "snake_case")]
+pub enum ModeloCrecimiento {
+    Gompertz(Gompertz),
+    VonBertalanffy(VonBertalanffy),
+    Logistico(Logistico),
+    Richards(Richards),
+    Ajuste { modelo: Familia, pesajes: Vec<(u32, f64)> },
+}
+
+impl ModeloCrecimiento {
+    pub fn crear(&self) -> Rc<dyn GrowthModel> {
+        match self {
+            ModeloCrecimiento::Gompertz(m) => Rc::new(*m),
+            ModeloCrecimiento::VonBertalanffy(m) => Rc::new(*m),
+            ModeloCrecimiento::Logistico(m) => Rc::new(*m),
+            ModeloCrecimiento::Richards(m) => Rc::new(*m),
+            // Sin `CrecimientoEspecie::ajustar_pesajes` antes, cada llamada vuelve a ajustar.
+            ModeloCrecimiento::Ajuste { modelo, pesajes } => ajustar(*modelo, pesajes).crear(),
+        }
+    }
+
+    // Fuera de estos rangos la curva da pesos negativos o se queda en cero.
+    pub fn validar(&self) -> Result<(), String> {
+        let (modelo, pesajes) = match self {
+            ModeloCrecimiento::Gompertz(Gompertz { a, b, k }) | ModeloCrecimiento::Logistico(Logistico { a, b, k }) => {
+                return positivo("a", *a).and(positivo("b", *b)).and(positivo("k", *k));
+            }
+            ModeloCrecimiento::VonBertalanffy(VonBertalanffy { a, b, k }) => {
+                return positivo("a", *a).and(entre_0_y_1("b", *b)).and(positivo("k", *k));
+            }
+            ModeloCrecimiento::Richards(Richards { a, b, k, m }) => {
+                return positivo("a", *a).and(entre_0_y_1("b", *b)).and(positivo("k", *k)).and(positivo("m", *m));
+            }
+            ModeloCrecimiento::Ajuste { modelo, pesajes } => (modelo, pesajes),
+        };
+        if pesajes.len() < modelo.parametros() {
+            return Err(format!(
+                "ajustar {:?} pide al menos {} pesajes y hay {}",
+                modelo,
+                modelo.parametros(),
+                pesajes.len()
+            ));
+        }
+        if pesajes.iter().any(|&(_, peso)| peso.is_nan() || peso <= 0.0) {
+            return Err("los pesajes tienen que ser positivos".to_string());
+        }
+        Ok(())
+    }
+}
+
+fn positivo(nombre: &str, valor: f64) -> Result<(), String> {
+    if valor > 0.0 { Ok(()) } else { Err(format!("{} tiene que ser mayor que 0 (vale {})", nombre, valor)) }
+}
+
+fn entre_0_y_1(nombre: &str, valor: f64) -> Result<(), String> {
+    if valor > 0.0 && valor < 1.0 {
+        Ok(())
+    } else {
+        Err(format!("{} tiene que estar entre 0 y 1, sin incluirlos (vale {})", nombre, valor))
+    }
+}
+
+impl Familia {
+    fn parametros(&self) -> usize {
+        match self {
+            Familia::Richards => 4,
+            _ => 3,
+        }
+    }
+
+    // Los parámetros se buscan en un espacio sin restricciones: `a`, `k` y `m` como logaritmos y,
+    // en las curvas donde `b` debe quedar entre 0 y 1, `b` como logit.
+    fn modelo(&self, x: &[f64]) -> ModeloCrecimiento {
+        let (a, k) = (x[0].exp(), x[2].exp());
+        let b_positivo = x[1].exp();
+        let b_fraccion = 1.0 / (1.0 + (-x[1]).exp());
+        match self {
+            Familia::Gompertz => ModeloCrecimiento::Gompertz(Gompertz { a, b: b_positivo, k }),
+            Familia::VonBertalanffy => ModeloCrecimiento::VonBertalanffy(VonBertalanffy { a, b: b_fraccion, k }),
+            Familia::Logistico => ModeloCrecimiento::Logistico(Logistico { a, b: b_positivo, k }),
+            Familia::Richards => ModeloCrecimiento::Richards(Richards { a, b: b_fraccion, k, m: x[3].exp() }),
+        }
+    }
+
+    // Un punto de partida razonable: la asíntota un poco por encima del mayor pesaje, `b` para que
+    // la curva pase por el primer pesaje y `k` para llegar cerca de la asíntota en la mayor edad.
+    fn inicial(&self, pesajes: &[(u32, f64)]) -> Vec<f64> {
+        let maximo = pesajes.iter().map(|&(_, p)| p).fold(f64::MIN, f64::max);
+        let &(_, primero) = pesajes.iter().min_by_key(|&&(edad, _)| edad).expect("hay pesajes");
+        let ultima_edad = pesajes.iter().map(|&(edad, _)| edad).max().unwrap_or(1).max(1) as f64;
+        let a = maximo * 1.1;
+        let r = (primero / a).clamp(1e-6, 0.999);
+        let logit = |p: f64| (p / (1.0 - p)).ln();
+        let mut x = match self {
+            Familia::Gompertz => vec![a.ln(), (-r.ln()).ln(), 0.0],
+            Familia::VonBertalanffy => vec![a.ln(), logit(1.0 - r.cbrt()), 0.0],
+            Familia::Logistico => vec![a.ln(), (1.0 / r - 1.0).ln(), 0.0],
+            Familia::Richards => vec![a.ln(), logit(1.0 - r.cbrt()), 0.0, 3.0_f64.ln()],
+        };
+        x[2] = (3.0 / ultima_edad).ln();
+        x
+    }
+}
+
+// Mínimos cuadrados sobre los pesajes, con Nelder-Mead. Es determinista: los mismos pesajes dan
+// siempre la misma curva.
+pub fn ajustar(modelo: Familia, pesajes: &[(u32, f64)]) -> ModeloCrecimiento {
+    if pesajes.is_empty() {
+        return modelo.modelo(&modelo.inicial(&[(0, 1.0)]));
+    }
+    let error = |x: &[f64]| -> f64 {
+        let curva = modelo.modelo(x).crear();
+        let sse: f64 = pesajes.iter().map(|&(edad, peso)| (curva.peso(edad) - peso).powi(2)).sum();
+        if sse.is_finite() { sse } else { f64::MAX }
+    };
+    let mut x = modelo.inicial(pesajes);
+    // Reiniciar desde el mejor punto evita que el simplex quede achatado en un valle.
+    for _ in 0..5 {
+        x = nelder_mead(&error, x, 2000);
+    }
+    modelo.modelo(&x)
+}
+
+fn nelder_mead(f: &impl Fn(&[f64]) -> f64, inicio: Vec<f64>, iteraciones: usize) -> Vec<f64> {
+    let n = inicio.len();
+    let mut simplex: Vec<(Vec<f64>, f64)> = vec![(inicio.clone(), f(&inicio))];
+    for i in 0..n {
+        let mut x = inicio.clone();
+        x[i] += 0.5;
+        let valor = f(&x);
+        simplex.push((x, valor));
+    }
+    let combinar = |p: &[f64], q: &[f64], t: f64| -> Vec<f64> { p.iter().zip(q).map(|(a, b)| a + t * (b - a)).collect() };
+
+    for _ in 0..iteraciones {
+        simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+        if (simplex[n].1 - simplex[0].1).abs() <= 1e-12 * (1.0 + simplex[0].1.abs()) {
+            break;
+        }
+        let centro: Vec<f64> = (0..n).map(|j| simplex[..n].iter().map(|(x, _)| x[j]).sum::<f64>() / n as f64).collect();
+        let peor = simplex[n].0.clone();
+        let reflejado = combinar(&centro, &peor, -1.0);
+        let f_reflejado = f(&reflejado);
+        if f_reflejado < simplex[0].1 {
+            let expandido = combinar(&centro, &peor, -2.0);
+            let f_expandido = f(&expandido);
+            simplex[n] = if f_expandido < f_reflejado { (expandido, f_expandido) } else { (reflejado, f_reflejado) };
+        } else if f_reflejado < simplex[n - 1].1 {
+            simplex[n] = (reflejado, f_reflejado);
+        } else {
+            let contraido = combinar(&centro, &peor, 0.5);
+            let f_contraido = f(&contraido);
+            if f_contraido < simplex[n].1 {
+                simplex[n] = (contraido, f_contraido);
+            } else {
+                let mejor = simplex[0].0.clone();
+                for (x, valor) in simplex.iter_mut().skip(1) {
+                    *x = combinar(&mejor, x, 0.5);
+                    *valor = f(x);
+                }
+            }
+        }
+    }
+    simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+    simplex.swap_remove(0).0
+}
+
+// La curva de machos y la de hembras de una especie.
+#[derive(Debug, Clone)]
+pub struct CurvasCrecimiento {
+    pub machos: Rc<dyn GrowthModel>,
+    pub hembras: Rc<dyn GrowthModel>,
+}
+
+impl CurvasCrecimiento {
+    pub fn de(&self, sex: Sex) -> &dyn GrowthModel {
+        match sex {
+            Sex::Male => self.machos.as_ref(),
+            Sex::Female => self.hembras.as_ref(),
+        }
+    }
+}
+
+// Crecimiento de una especie: `modelo` vale para los dos sexos salvo que `machos` o `hembras` lo
+// reemplacen.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct CrecimientoEspecie {
+    pub modelo: Option<ModeloCrecimiento>,
+    pub machos: Option<ModeloCrecimiento>,
+    pub hembras: Option<ModeloCrecimiento>,
+}
+
+impl CrecimientoEspecie {
+    fn de(&self, sex: Sex) -> Option<&ModeloCrecimiento> {
+        let propia = match sex {
+            Sex::Male => &self.machos,
+            Sex::Female => &self.hembras,
+        };
+        propia.as_ref().or(self.modelo.as_ref())
+    }
+
+    pub fn completo(&self) -> bool {
+        self.de(Sex::Male).is_some() && self.de(Sex::Female).is_some()
+    }
+
+    pub fn curvas(&self) -> Option<CurvasCrecimiento> {
+        Some(CurvasCrecimiento { machos: self.de(Sex::Male)?.crear(), hembras: self.de(Sex::Female)?.crear() })
+    }
+
+    // Este crecimiento encima de `base`: cada sexo toma la curva de acá si la hay, si no la de `base`.
+    pub fn sobre(&self, base: &CrecimientoEspecie) -> CrecimientoEspecie {
+        if self.completo() {
+            return self.clone();
+        }
+        CrecimientoEspecie {
+            modelo: None,
+            machos: self.de(Sex::Male).or(base.de(Sex::Male)).cloned(),
+            hembras: self.de(Sex::Female).or(base.de(Sex::Female)).cloned(),
+        }
+    }
+
+    // Reemplaza cada curva a ajustar por la curva ya ajustada, así se ajusta una sola vez. Devuelve
+    // las ajustadas con el sexo al que se aplican.
+    pub fn ajustar_pesajes(&mut self) -> Vec<(&'static str, ModeloCrecimiento)> {
+        let mut ajustadas = Vec::new();
+        let modelos = [("", &mut self.modelo), (" (machos)", &mut self.machos), (" (hembras)", &mut self.hembras)];
+        for (sexo, modelo) in modelos {
+            if let Some(ModeloCrecimiento::Ajuste { modelo: familia, pesajes }) = modelo {
+                let ajustado = ajustar(*familia, pesajes);
+                ajustadas.push((sexo, ajustado.clone()));
+                *modelo = Some(ajustado);
+            }
+        }
+        ajustadas
+    }
+
+    pub fn modelos(&self) -> impl Iterator<Item = &ModeloCrecimiento> {
+        self.modelo.iter().chain(&self.machos).chain(&self.hembras)
+    }
+}
+
+impl fmt::Display for ModeloCrecimiento {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModeloCrecimiento::Gompertz(m) => write!(f, "Gompertz a = {:.4}, b = {:.4}, k = {:.6}", m.a, m.b, m.k),
+            ModeloCrecimiento::VonBertalanffy(m) => {
+                write!(f, "von Bertalanffy a = {:.4}, b = {:.4}, k = {:.6}", m.a, m.b, m.k)
+            }
+            ModeloCrecimiento::Logistico(m) => write!(f, "logístico a = {:.4}, b = {:.4}, k = {:.6}", m.a, m.b, m.k),
+            ModeloCrecimiento::Richards(m) => {
+                write!(f, "Richards a = {:.4}, b = {:.4}, k = {:.6}, m = {:.4}", m.a, m.b, m.k, m.m)
+            }
+            ModeloCrecimiento::Ajuste { modelo, pesajes } => {
+                write!(f, "{:?} a ajustar a {} pesajes", modelo, pesajes.len())
+            }
+        }
+    }
+}
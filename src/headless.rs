@@ -0,0 +1,72 @@
+use simulacion::Simulation;
+use simulacion::history::{self, CsvStream};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufWriter};
+
+pub struct Salidas {
+    pub csv: Option<String>,
+    pub json: Option<String>,
+    pub dot: Option<String>,
+    pub genealogia: Option<String>,
+}
+
+// Corre la simulación completa sin ventana y deja un resumen al final.
+// El CSV se va escribiendo día a día; el JSON y la genealogía se escriben al terminar.
+pub fn run(mut sim: Simulation, total_dias: u32, salidas: &Salidas) -> io::Result<()> {
+    let mut csv = match &salidas.csv {
+        Some(ruta) => Some(CsvStream::new(BufWriter::new(File::create(ruta)?))),
+        None => None,
+    };
+
+    for _ in 0..total_dias {
+        if sim.terminada() {
+            break;
+        }
+        sim.simulate_day();
+        if let (Some(csv), Some(registro)) = (csv.as_mut(), sim.ultimo_registro()) {
+            csv.escribir(registro)?;
+        }
+    }
+
+    if let Some(csv) = csv.as_mut() {
+        csv.flush()?;
+    }
+    if let Some(ruta) = &salidas.json {
+        history::escribir_json(sim.get_history(), BufWriter::new(File::create(ruta)?))?;
+    }
+    if let Some(ruta) = &salidas.dot {
+        sim.get_genealogia().escribir_dot(BufWriter::new(File::create(ruta)?))?;
+    }
+    if let Some(ruta) = &salidas.genealogia {
+        sim.get_genealogia().escribir_json(BufWriter::new(File::create(ruta)?))?;
+    }
+
+    let mut conteo: BTreeMap<&str, usize> = BTreeMap::new();
+    for a in sim.get_animals() {
+        *conteo.entry(a.nombre()).or_insert(0) += 1;
+    }
+
+    println!("\n=== Resumen tras {} días (semilla {}) ===", sim.get_day(), sim.get_seed());
+    println!("Población final: {} animales", sim.get_animals().len());
+    for (nombre, cantidad) in conteo {
+        println!("{}: {}", nombre, cantidad);
+    }
+    let mut depredadores: BTreeMap<&str, usize> = BTreeMap::new();
+    for p in sim.get_predators() {
+        *depredadores.entry(&p.especie).or_insert(0) += 1;
+    }
+    if depredadores.is_empty() {
+        println!("No quedan depredadores.");
+    }
+    for (especie, cantidad) in depredadores {
+        println!("{}: {}", especie, cantidad);
+    }
+    let reserva = sim.ultimo_registro().map_or(0.0, |r| r.reserva_depredador);
+    println!("Reserva final de los depredadores: {:.2}", reserva);
+    let costo_veterinario: f64 = sim.get_history().iter().map(|r| r.costos_veterinarios.total()).sum();
+    if costo_veterinario > 0.0 {
+        println!("Costo veterinario total: {:.2}", costo_veterinario);
+    }
+    Ok(())
+}
@@ -0,0 +1,400 @@
+use crate::disease::{EstadoSalud, RegistroEnfermedades};
+use crate::events::Evento;
+use crate::organism::predator::Predator;
+use crate::pasture::Pastoreo;
+use crate::species::Species;
+use crate::traits::Organismo;
+use crate::veterinary::CostosVeterinarios;
+use serde::Serialize;
+use std::io::{self, Write};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConteoEspecie {
+    pub species: Species,
+    pub machos: usize,
+    pub hembras: usize,
+    pub gestantes: usize,
+    pub biomasa: f64,
+    // Condición corporal media; 1 si no queda ninguno.
+    pub condicion: f64,
+    pub cazados: usize,
+    pub escapes: usize,
+    // Kilos de pasto pedidos y comidos en el día; cero si la corrida no tiene pastura.
+    pub forraje_demandado: f64,
+    pub forraje_comido: f64,
+}
+
+// Los depredadores de una especie al cerrar el día.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConteoDepredador {
+    pub especie: String,
+    pub vivos: usize,
+    // Los que hoy no llegaron a comer lo mínimo.
+    pub hambrientos: usize,
+    pub nacimientos: usize,
+    pub muertes: usize,
+    pub reserva: f64,
+    pub consumido_hoy: f64,
+    // Reserva echada a perder o que no entró por capacidad.
+    pub perdido: f64,
+}
+
+// Compartimentos SEIR de una enfermedad en una de sus especies huésped: susceptibles, expuestos
+// (en incubación), infecciosos y recuperados.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConteoEnfermedad {
+    pub enfermedad: String,
+    pub species: Species,
+    pub susceptibles: usize,
+    pub expuestos: usize,
+    pub infecciosos: usize,
+    pub recuperados: usize,
+}
+
+// Una fila por día: lo que antes solo quedaba en los println!.
+#[derive(Debug, Clone, Serialize)]
+pub struct DayRecord {
+    pub dia: u32,
+    pub especies: Vec<ConteoEspecie>,
+    pub depredadores: Vec<ConteoDepredador>,
+    pub enfermedades: Vec<ConteoEnfermedad>,
+    // Biomasa de la pastura al cerrar el día; `None` si la corrida no tiene pastura.
+    pub pasto: Option<f64>,
+    pub biomasa_total: f64,
+    pub reserva_depredador: f64,
+    pub consumido_hoy: f64,
+    pub depredadores_vivos: usize,
+    pub nacimientos: usize,
+    pub muertes_enfermedad: usize,
+    pub muertes_vejez: usize,
+    pub muertes_hambre: usize,
+    pub cazados: usize,
+    pub cazas_fallidas: usize,
+    pub vacunados: usize,
+    pub tratados: usize,
+    pub curados: usize,
+    pub aislados: usize,
+    pub costos_veterinarios: CostosVeterinarios,
+}
+
+// Las especies de la corrida, también las que ya no tienen individuos, para que las columnas no
+// cambien de un día a otro.
+#[derive(Debug, Clone, Copy)]
+pub struct Especies<'a> {
+    pub presas: &'a [Species],
+    pub depredadores: &'a [String],
+}
+
+impl DayRecord {
+    pub fn tomar(
+        dia: u32,
+        animals: &[Box<dyn Organismo>],
+        predators: &[Predator],
+        especies_corrida: Especies,
+        enfermedades: &RegistroEnfermedades,
+        costos_veterinarios: CostosVeterinarios,
+        eventos: &[Evento],
+    ) -> Self {
+        let mut especies: Vec<ConteoEspecie> = especies_corrida
+            .presas
+            .iter()
+            .map(|species| ConteoEspecie {
+                species: species.clone(),
+                machos: 0,
+                hembras: 0,
+                gestantes: 0,
+                biomasa: 0.0,
+                condicion: 0.0,
+                cazados: contar(eventos, |e| matches!(e, Evento::Hunted { species: s, .. } if s == species)),
+                escapes: contar(eventos, |e| matches!(e, Evento::HuntFailed { species: s, .. } if s == species)),
+                forraje_demandado: 0.0,
+                forraje_comido: 0.0,
+            })
+            .collect();
+
+        let depredadores: Vec<ConteoDepredador> = especies_corrida
+            .depredadores
+            .iter()
+            .map(|especie| {
+                let vivos: Vec<&Predator> = predators.iter().filter(|p| &p.especie == especie).collect();
+                ConteoDepredador {
+                    especie: especie.clone(),
+                    vivos: vivos.len(),
+                    hambrientos: vivos.iter().filter(|p| p.enfermo).count(),
+                    nacimientos: contar(eventos, |e| {
+                        matches!(e, Evento::PredatorBorn { species, .. } if species == especie)
+                    }),
+                    muertes: contar(eventos, |e| {
+                        matches!(e, Evento::PredatorDied { species, .. } if species == especie)
+                    }),
+                    reserva: sumar(vivos.iter().map(|p| p.current_reserve())),
+                    consumido_hoy: sumar(vivos.iter().map(|p| p.consumido_hoy)),
+                    perdido: sumar(vivos.iter().map(|p| p.perdido_hoy)),
+                }
+            })
+            .collect();
+
+        // (índice de enfermedad, conteo) por cada especie huésped.
+        let mut seir: Vec<(usize, ConteoEnfermedad)> = Vec::new();
+        for (idx, enfermedad) in enfermedades.iter() {
+            for species in enfermedad.huespedes.keys() {
+                // La del registro de especies, que trae el nombre.
+                let species = especies_corrida.presas.iter().find(|&s| s == species).unwrap_or(species);
+                seir.push((
+                    idx,
+                    ConteoEnfermedad {
+                        enfermedad: enfermedad.nombre.clone(),
+                        species: species.clone(),
+                        susceptibles: 0,
+                        expuestos: 0,
+                        infecciosos: 0,
+                        recuperados: 0,
+                    },
+                ));
+            }
+        }
+
+        for a in animals {
+            let (Some(especie), Some(es_macho)) = (a.especie(), a.es_macho()) else {
+                continue;
+            };
+            if let Some(c) = especies.iter_mut().find(|c| c.species == especie) {
+                if es_macho {
+                    c.machos += 1;
+                } else {
+                    c.hembras += 1;
+                }
+                if a.gestacion().is_some() {
+                    c.gestantes += 1;
+                }
+                c.biomasa += a.peso();
+                c.condicion += a.condicion().unwrap_or(1.0);
+            }
+            for (idx, c) in seir.iter_mut().filter(|(_, c)| c.species == especie) {
+                match a.salud(*idx) {
+                    Some(EstadoSalud::Susceptible) => c.susceptibles += 1,
+                    Some(EstadoSalud::Incubando { .. }) => c.expuestos += 1,
+                    Some(EstadoSalud::Enfermo) => c.infecciosos += 1,
+                    Some(EstadoSalud::Inmune { .. }) => c.recuperados += 1,
+                    None => {}
+                }
+            }
+        }
+
+        for c in &mut especies {
+            let cantidad = c.machos + c.hembras;
+            c.condicion = if cantidad > 0 { c.condicion / cantidad as f64 } else { 1.0 };
+        }
+
+        DayRecord {
+            dia,
+            biomasa_total: especies.iter().map(|c| c.biomasa).sum(),
+            especies,
+            enfermedades: seir.into_iter().map(|(_, c)| c).collect(),
+            pasto: None,
+            reserva_depredador: sumar(depredadores.iter().map(|c| c.reserva)),
+            consumido_hoy: sumar(depredadores.iter().map(|c| c.consumido_hoy)),
+            depredadores_vivos: depredadores.iter().map(|c| c.vivos).sum(),
+            depredadores,
+            nacimientos: contar(eventos, |e| matches!(e, Evento::Birth { .. })),
+            muertes_enfermedad: contar(eventos, |e| matches!(e, Evento::DiedOfDisease { .. })),
+            muertes_vejez: contar(eventos, |e| matches!(e, Evento::DiedOfOldAge { .. })),
+            muertes_hambre: contar(eventos, |e| matches!(e, Evento::StarvedToDeath { .. })),
+            cazados: contar(eventos, |e| matches!(e, Evento::Hunted { .. })),
+            cazas_fallidas: contar(eventos, |e| matches!(e, Evento::HuntFailed { .. })),
+            vacunados: contar(eventos, |e| matches!(e, Evento::Vaccinated { .. })),
+            tratados: contar(eventos, |e| matches!(e, Evento::Treated { .. })),
+            curados: contar(eventos, |e| matches!(e, Evento::Treated { cured: true, .. })),
+            aislados: animals.iter().filter(|a| a.aislado()).count(),
+            costos_veterinarios,
+        }
+    }
+
+    pub fn agregar_pastoreo(&mut self, pastoreo: &Pastoreo) {
+        self.pasto = Some(pastoreo.biomasa);
+        for c in &mut self.especies {
+            c.forraje_demandado = pastoreo.demandado.get(&c.species).copied().unwrap_or(0.0);
+            c.forraje_comido = pastoreo.comido.get(&c.species).copied().unwrap_or(0.0);
+        }
+    }
+
+    pub fn total(&self, species: &Species) -> usize {
+        self.especies
+            .iter()
+            .find(|c| &c.species == species)
+            .map_or(0, |c| c.machos + c.hembras)
+    }
+
+    fn encabezado_csv(&self) -> String {
+        let mut columnas = vec!["dia".to_string()];
+        for c in &self.especies {
+            let nombre = c.species.nombre().to_lowercase().replace([' ', ','], "_");
+            columnas.push(format!("{}_machos", nombre));
+            columnas.push(format!("{}_hembras", nombre));
+            columnas.push(format!("{}_gestantes", nombre));
+            columnas.push(format!("{}_biomasa", nombre));
+            columnas.push(format!("{}_condicion", nombre));
+            columnas.push(format!("{}_cazados", nombre));
+            columnas.push(format!("{}_escapes", nombre));
+            columnas.push(format!("{}_forraje_demandado", nombre));
+            columnas.push(format!("{}_forraje_comido", nombre));
+        }
+        for c in &self.depredadores {
+            let nombre = c.especie.to_lowercase().replace([' ', ','], "_");
+            columnas.push(format!("{}_vivos", nombre));
+            columnas.push(format!("{}_hambrientos", nombre));
+            columnas.push(format!("{}_nacimientos", nombre));
+            columnas.push(format!("{}_muertes", nombre));
+            columnas.push(format!("{}_reserva", nombre));
+            columnas.push(format!("{}_perdido", nombre));
+        }
+        for c in &self.enfermedades {
+            let prefijo = format!(
+                "{}_{}",
+                c.enfermedad.to_lowercase().replace([' ', ','], "_"),
+                c.species.nombre().to_lowercase().replace([' ', ','], "_")
+            );
+            for compartimento in ["s", "e", "i", "r"] {
+                columnas.push(format!("{}_{}", prefijo, compartimento));
+            }
+        }
+        columnas.extend(
+            [
+                "pasto",
+                "biomasa_total",
+                "reserva_depredador",
+                "consumido_hoy",
+                "depredadores_vivos",
+                "nacimientos",
+                "muertes_enfermedad",
+                "muertes_vejez",
+                "muertes_hambre",
+                "cazados",
+                "cazas_fallidas",
+                "vacunados",
+                "tratados",
+                "curados",
+                "aislados",
+                "costo_vacunacion",
+                "costo_tratamiento",
+                "costo_cuarentena",
+            ]
+            .map(String::from),
+        );
+        columnas.join(",")
+    }
+
+    fn fila_csv(&self) -> String {
+        let mut valores = vec![self.dia.to_string()];
+        for c in &self.especies {
+            valores.push(c.machos.to_string());
+            valores.push(c.hembras.to_string());
+            valores.push(c.gestantes.to_string());
+            valores.push(format!("{:.3}", c.biomasa));
+            valores.push(format!("{:.3}", c.condicion));
+            valores.push(c.cazados.to_string());
+            valores.push(c.escapes.to_string());
+            valores.push(format!("{:.3}", c.forraje_demandado));
+            valores.push(format!("{:.3}", c.forraje_comido));
+        }
+        for c in &self.depredadores {
+            valores.push(c.vivos.to_string());
+            valores.push(c.hambrientos.to_string());
+            valores.push(c.nacimientos.to_string());
+            valores.push(c.muertes.to_string());
+            valores.push(format!("{:.3}", c.reserva));
+            valores.push(format!("{:.3}", c.perdido));
+        }
+        for c in &self.enfermedades {
+            valores.push(c.susceptibles.to_string());
+            valores.push(c.expuestos.to_string());
+            valores.push(c.infecciosos.to_string());
+            valores.push(c.recuperados.to_string());
+        }
+        // Sin pastura la columna queda vacía.
+        valores.push(self.pasto.map_or(String::new(), |p| format!("{:.3}", p)));
+        valores.push(format!("{:.3}", self.biomasa_total));
+        valores.push(format!("{:.3}", self.reserva_depredador));
+        valores.push(format!("{:.3}", self.consumido_hoy));
+        valores.push(self.depredadores_vivos.to_string());
+        valores.push(self.nacimientos.to_string());
+        valores.push(self.muertes_enfermedad.to_string());
+        valores.push(self.muertes_vejez.to_string());
+        valores.push(self.muertes_hambre.to_string());
+        valores.push(self.cazados.to_string());
+        valores.push(self.cazas_fallidas.to_string());
+        valores.push(self.vacunados.to_string());
+        valores.push(self.tratados.to_string());
+        valores.push(self.curados.to_string());
+        valores.push(self.aislados.to_string());
+        valores.push(format!("{:.3}", self.costos_veterinarios.vacunacion));
+        valores.push(format!("{:.3}", self.costos_veterinarios.tratamiento));
+        valores.push(format!("{:.3}", self.costos_veterinarios.cuarentena));
+        valores.join(",")
+    }
+}
+
+// `sum()` de f64 sin elementos da -0.0, que en el CSV sale como "-0.000".
+fn sumar(valores: impl Iterator<Item = f64>) -> f64 {
+    valores.fold(0.0, |total, v| total + v)
+}
+
+fn contar(eventos: &[Evento], filtro: impl Fn(&Evento) -> bool) -> usize {
+    eventos.iter().filter(|e| filtro(e)).count()
+}
+
+pub fn escribir_csv(registros: &[DayRecord], w: impl Write) -> io::Result<()> {
+    let mut stream = CsvStream::new(w);
+    for r in registros {
+        stream.escribir(r)?;
+    }
+    stream.flush()
+}
+
+pub fn escribir_json(registros: &[DayRecord], w: impl Write) -> io::Result<()> {
+    serde_json::to_writer_pretty(w, registros).map_err(io::Error::from)
+}
+
+// Escribe las filas a medida que se simulan los días; el encabezado sale con la primera.
+pub struct CsvStream<W: Write> {
+    w: W,
+    con_encabezado: bool,
+}
+
+impl<W: Write> CsvStream<W> {
+    pub fn new(w: W) -> Self {
+        CsvStream { w, con_encabezado: false }
+    }
+
+    pub fn escribir(&mut self, registro: &DayRecord) -> io::Result<()> {
+        if !self.con_encabezado {
+            writeln!(self.w, "{}", registro.encabezado_csv())?;
+            self.con_encabezado = true;
+        }
+        writeln!(self.w, "{}", registro.fila_csv())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.w.flush()
+    }
+}
+
+// Un objeto JSON por línea (JSON Lines), para seguir la corrida mientras avanza.
+pub struct JsonLinesStream<W: Write> {
+    w: W,
+}
+
+impl<W: Write> JsonLinesStream<W> {
+    pub fn new(w: W) -> Self {
+        JsonLinesStream { w }
+    }
+
+    pub fn escribir(&mut self, registro: &DayRecord) -> io::Result<()> {
+        serde_json::to_writer(&mut self.w, registro).map_err(io::Error::from)?;
+        writeln!(self.w)
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.w.flush()
+    }
+}
@@ -0,0 +1,239 @@
+use crate::organism::prey::Sex;
+use crate::species::Species;
+use crate::traits::Organismo;
+use rand::RngCore;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::seq::SliceRandom;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::rc::Rc;
+
+// Elige, entre las presas que el depredador puede cazar, cuál caza. Devuelve el índice dentro de
+// `candidatos`, o `None` para no cazar.
+pub trait HuntingStrategy: fmt::Debug {
+    fn elegir(&self, candidatos: &[&dyn Organismo], rng: &mut dyn RngCore) -> Option<usize>;
+}
+
+// El candidato de mayor `clave`; los empates se deciden al azar.
+fn elegir_maximo(
+    candidatos: &[&dyn Organismo],
+    rng: &mut dyn RngCore,
+    clave: impl Fn(&dyn Organismo) -> f64,
+) -> Option<usize> {
+    let claves: Vec<f64> = candidatos.iter().map(|c| clave(*c)).collect();
+    let maximo = claves.iter().copied().fold(f64::MIN, f64::max);
+    let mejores: Vec<usize> = (0..claves.len())
+        .filter(|&i| (claves[i] - maximo).abs() < f64::EPSILON)
+        .collect();
+    mejores.choose(rng).copied()
+}
+
+#[derive(Debug)]
+pub struct MasPesada;
+
+impl HuntingStrategy for MasPesada {
+    fn elegir(&self, candidatos: &[&dyn Organismo], rng: &mut dyn RngCore) -> Option<usize> {
+        elegir_maximo(candidatos, rng, |c| c.peso())
+    }
+}
+
+#[derive(Debug)]
+pub struct MasLiviana;
+
+impl HuntingStrategy for MasLiviana {
+    fn elegir(&self, candidatos: &[&dyn Organismo], rng: &mut dyn RngCore) -> Option<usize> {
+        elegir_maximo(candidatos, rng, |c| -c.peso())
+    }
+}
+
+#[derive(Debug)]
+pub struct MasJoven;
+
+impl HuntingStrategy for MasJoven {
+    fn elegir(&self, candidatos: &[&dyn Organismo], rng: &mut dyn RngCore) -> Option<usize> {
+        elegir_maximo(candidatos, rng, |c| -(c.edad().unwrap_or(0) as f64))
+    }
+}
+
+#[derive(Debug)]
+pub struct AlAzar;
+
+impl HuntingStrategy for AlAzar {
+    fn elegir(&self, candidatos: &[&dyn Organismo], rng: &mut dyn RngCore) -> Option<usize> {
+        let indices: Vec<usize> = (0..candidatos.len()).collect();
+        indices.choose(rng).copied()
+    }
+}
+
+// Primero los enfermos y, entre ellos (o entre todos, si no hay enfermos), el más débil: el más liviano.
+#[derive(Debug)]
+pub struct EnfermasPrimero;
+
+impl HuntingStrategy for EnfermasPrimero {
+    fn elegir(&self, candidatos: &[&dyn Organismo], rng: &mut dyn RngCore) -> Option<usize> {
+        let hay_enfermos = candidatos.iter().any(|c| c.esta_enfermo());
+        elegir_maximo(candidatos, rng, |c| {
+            if hay_enfermos && !c.esta_enfermo() { f64::MIN } else { -c.peso() }
+        })
+    }
+}
+
+// Elige al azar con un peso por especie; las especies que no figuran pesan 1 y las de peso 0 no se cazan.
+#[derive(Debug)]
+pub struct PreferenciaEspecie {
+    pub pesos: BTreeMap<Species, f64>,
+}
+
+impl HuntingStrategy for PreferenciaEspecie {
+    fn elegir(&self, candidatos: &[&dyn Organismo], rng: &mut dyn RngCore) -> Option<usize> {
+        let pesos: Vec<f64> = candidatos
+            .iter()
+            .map(|c| c.especie().and_then(|s| self.pesos.get(&s)).copied().unwrap_or(1.0).max(0.0))
+            .collect();
+        let distribucion = WeightedIndex::new(&pesos).ok()?;
+        Some(distribucion.sample(rng))
+    }
+}
+
+// Forrajeo óptimo: la presa que más kilos rinde por unidad de esfuerzo de manejo de su especie
+// (1 si la especie no figura).
+#[derive(Debug)]
+pub struct ForrajeoOptimo {
+    pub esfuerzo: BTreeMap<Species, f64>,
+}
+
+impl HuntingStrategy for ForrajeoOptimo {
+    fn elegir(&self, candidatos: &[&dyn Organismo], rng: &mut dyn RngCore) -> Option<usize> {
+        elegir_maximo(candidatos, rng, |c| {
+            let esfuerzo = c.especie().and_then(|s| self.esfuerzo.get(&s)).copied().unwrap_or(1.0);
+            c.peso() / esfuerzo.max(f64::EPSILON)
+        })
+    }
+}
+
+// Las estrategias que se pueden elegir desde el escenario.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(tag = "tipo", rename_all = "snake_case")]
+pub enum EstrategiaCaza {
+    #[default]
+    MasPesada,
+    MasLiviana,
+    MasJoven,
+    AlAzar,
+    EnfermasPrimero,
+    PreferenciaEspecie { pesos: BTreeMap<Species, f64> },
+    ForrajeoOptimo {
+        #[serde(default)]
+        esfuerzo: BTreeMap<Species, f64>,
+    },
+}
+
+impl EstrategiaCaza {
+    // Las especies que nombra la configuración de la estrategia.
+    pub fn especies(&self) -> Vec<&Species> {
+        match self {
+            EstrategiaCaza::PreferenciaEspecie { pesos } => pesos.keys().collect(),
+            EstrategiaCaza::ForrajeoOptimo { esfuerzo } => esfuerzo.keys().collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn crear(&self) -> Rc<dyn HuntingStrategy> {
+        match self {
+            EstrategiaCaza::MasPesada => Rc::new(MasPesada),
+            EstrategiaCaza::MasLiviana => Rc::new(MasLiviana),
+            EstrategiaCaza::MasJoven => Rc::new(MasJoven),
+            EstrategiaCaza::AlAzar => Rc::new(AlAzar),
+            EstrategiaCaza::EnfermasPrimero => Rc::new(EnfermasPrimero),
+            EstrategiaCaza::PreferenciaEspecie { pesos } => Rc::new(PreferenciaEspecie { pesos: pesos.clone() }),
+            EstrategiaCaza::ForrajeoOptimo { esfuerzo } => Rc::new(ForrajeoOptimo { esfuerzo: esfuerzo.clone() }),
+        }
+    }
+}
+
+// Qué tan difícil es cazar a una especie. La probabilidad de éxito contra un adulto sano y solo es
+// `prob_exito` (sin valor, la del depredador) y se multiplica por los factores de la presa:
+// crías y viejos son más fáciles, los enfermos también, los más pesados que `peso_referencia` cuestan
+// más y cada compañero del mismo grupo de contacto suma `defensa_grupo` a la defensa.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Escape {
+    pub prob_exito: Option<f64>,
+    pub edad_cria: u32,
+    pub factor_cria: f64,
+    pub edad_vejez: Option<u32>,
+    pub factor_vejez: f64,
+    pub peso_referencia: Option<f64>,
+    pub exponente_peso: f64,
+    pub factor_enfermo: f64,
+    pub defensa_grupo: f64,
+}
+
+impl Default for Escape {
+    fn default() -> Self {
+        Escape {
+            prob_exito: None,
+            edad_cria: 0,
+            factor_cria: 1.5,
+            edad_vejez: None,
+            factor_vejez: 1.5,
+            peso_referencia: None,
+            exponente_peso: 1.0,
+            factor_enfermo: 1.5,
+            defensa_grupo: 0.0,
+        }
+    }
+}
+
+impl Escape {
+    // `grupo` es cuántos de su especie y grupo de contacto hay, contando a la presa.
+    pub fn prob_exito(&self, prob_base: f64, presa: &dyn Organismo, grupo: usize) -> f64 {
+        let mut p = self.prob_exito.unwrap_or(prob_base);
+        let edad = presa.edad().unwrap_or(0);
+        if edad < self.edad_cria {
+            p *= self.factor_cria;
+        }
+        if self.edad_vejez.is_some_and(|vejez| edad >= vejez) {
+            p *= self.factor_vejez;
+        }
+        if let Some(referencia) = self.peso_referencia
+            && presa.peso() > 0.0
+        {
+            p *= (referencia / presa.peso()).powf(self.exponente_peso);
+        }
+        if presa.esta_enfermo() {
+            p *= self.factor_enfermo;
+        }
+        p /= 1.0 + self.defensa_grupo * grupo.saturating_sub(1) as f64;
+        p.clamp(0.0, 1.0)
+    }
+}
+
+// Qué individuos de una especie puede cazar el depredador. Sin `edad_min` vale su `sacrifice_age_days`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ReglaSacrificio {
+    pub edad_min: Option<u32>,
+    pub edad_max: Option<u32>,
+    pub peso_min: f64,
+    // Solo machos o solo hembras; sin valor, ambos.
+    pub sexo: Option<Sex>,
+}
+
+impl ReglaSacrificio {
+    pub fn admite(&self, presa: &dyn Organismo, edad_min_por_defecto: u32) -> bool {
+        let Some(edad) = presa.edad() else {
+            return false;
+        };
+        let sexo_ok = match (self.sexo, presa.es_macho()) {
+            (None, _) => true,
+            (Some(sexo), Some(es_macho)) => es_macho == (sexo == Sex::Male),
+            (Some(_), None) => false,
+        };
+        edad >= self.edad_min.unwrap_or(edad_min_por_defecto)
+            && self.edad_max.is_none_or(|max| edad <= max)
+            && presa.peso() >= self.peso_min
+            && sexo_ok
+    }
+}
@@ -0,0 +1,29 @@
+pub mod traits;
+mod util;
+pub mod organism {
+    pub mod prey;
+    pub mod predator;
+}
+pub mod sim;
+pub mod scenario;
+pub mod history;
+pub mod events;
+pub mod genealogy;
+pub mod mating;
+pub mod reproduction;
+pub mod disease;
+pub mod veterinary;
+pub mod hunting;
+pub mod pasture;
+pub mod growth;
+pub mod species;
+
+pub use crate::events::{Evento, LogSuscriptor, Suscriptor};
+pub use crate::genealogy::Genealogia;
+pub use crate::history::DayRecord;
+pub use crate::organism::predator::Predator;
+pub use crate::organism::prey::{Prey, Sex};
+pub use crate::scenario::{Scenario, ScenarioError};
+pub use crate::sim::Simulation;
+pub use crate::species::Species;
+pub use crate::traits::Organismo;
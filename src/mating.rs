@@ -0,0 +1,31 @@
+use crate::species::Species;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+// Cuántas hembras puede cubrir cada macho por temporada; la cuenta se reinicia al empezar otra.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ConfigApareamiento {
+    pub duracion_temporada: u32,
+    pub capacidad_por_temporada: BTreeMap<Species, u32>,
+}
+
+impl Default for ConfigApareamiento {
+    fn default() -> Self {
+        ConfigApareamiento {
+            duracion_temporada: 365,
+            capacidad_por_temporada: BTreeMap::new(),
+        }
+    }
+}
+
+impl ConfigApareamiento {
+    pub fn temporada(&self, dia: u32) -> u32 {
+        dia.saturating_sub(1) / self.duracion_temporada.max(1)
+    }
+
+    // Sin valor en el escenario vale la capacidad de la especie en el registro.
+    pub fn capacidad(&self, species: &Species) -> Option<u32> {
+        self.capacidad_por_temporada.get(species).copied()
+    }
+}
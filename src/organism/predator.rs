@@ -1,15 +1,92 @@
+use crate::events::Evento;
+use crate::genealogy::CausaMuerte;
+use crate::hunting::{Escape, HuntingStrategy, MasPesada, ReglaSacrificio};
+use crate::sim::GeneradorIds;
 use crate::traits::Organismo;
-use crate::organism::prey::Species;  
-use rand::rngs::ThreadRng;
-use rand::seq::SliceRandom;          
+use crate::util::probabilidad;
+use crate::species::Species;  
+use rand::{Rng, RngCore};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::rc::Rc;
 
+// Qué hace la simulación cuando se extingue una especie de depredador.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(tag = "tipo", rename_all = "snake_case")]
+pub enum AlExtinguirse {
+    // Las presas siguen solas hasta el último día.
+    #[default]
+    Continuar,
+    Terminar,
+    // Llega un depredador nuevo de la especie `dias` días después de la extinción.
+    Reemplazar { dias: u32 },
+}
+
+// Cría de los depredadores bien alimentados: un adulto con al menos `reserva_minima` en reserva y sin
+// cría en los últimos `intervalo` días tiene una camada, y cada cachorro se lleva `reserva_por_cria`
+// de la reserva del padre.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ParametrosCria {
+    pub edad_madurez: u32,
+    pub reserva_minima: f64,
+    pub intervalo: u32,
+    #[serde(default = "camada_por_defecto")]
+    pub camada_min: u32,
+    #[serde(default = "camada_por_defecto")]
+    pub camada_max: u32,
+    pub reserva_por_cria: f64,
+}
+
+fn camada_por_defecto() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone)]
 pub struct Predator {
+    pub id: u32,
+    pub especie: String,
     pub min_reserve: f64,        
     pub opt_reserve: f64,        
     pub current_reserve: f64,    
     pub sacrifice_age_days: u32, 
+    // Reglas por especie; las que no figuran se cazan desde `sacrifice_age_days`.
+    pub sacrificio: BTreeMap<Species, ReglaSacrificio>,
     pub enfermo: bool,           
     pub consumido_hoy: f64,      
+    pub initial_reserve: f64,
+    pub age_days: u32,
+    // Días seguidos sin alcanzar el mínimo; comer lo suficiente un día los vuelve a cero.
+    pub dias_hambre: u32,
+    pub max_dias_hambre: u32,
+    pub edad_maxima: u32,
+    pub vivo: bool,
+    pub padre: Option<u32>,
+    pub dia_nacimiento: u32,
+    // Sin parámetros de cría el depredador no se reproduce.
+    pub cria: Option<ParametrosCria>,
+    pub ultima_cria: Option<u32>,
+    pub al_extinguirse: AlExtinguirse,
+    pub estrategia: Rc<dyn HuntingStrategy>,
+    // Sin máximo de presas, caza hasta llegar a `opt_reserve`.
+    pub max_cazas_por_dia: Option<u32>,
+    pub max_intentos_por_dia: u32,
+    // Probabilidad de éxito de un intento; las especies de `escape` la ajustan según la presa.
+    pub prob_exito: f64,
+    pub escape: BTreeMap<Species, Escape>,
+    // Kilos que cuesta cada intento fallido.
+    pub costo_fallo: f64,
+    pub cazas_hoy: u32,
+    pub intentos_hoy: u32,
+    // Lo que no entra en la reserva se pierde; sin capacidad, entra todo.
+    pub capacidad_reserva: Option<f64>,
+    // Fracción de la reserva que se echa a perder cada día.
+    pub deterioro_diario: f64,
+    // Gasto metabólico diario, que se suma a `min_reserve`: `tasa_metabolica * masa_corporal^0.75`
+    // (ley de Kleiber) más `costo_por_intento` por cada intento de caza del día.
+    pub masa_corporal: f64,
+    pub tasa_metabolica: f64,
+    pub costo_por_intento: f64,
+    pub perdido_hoy: f64,
 }
 
 impl Predator {
@@ -21,126 +98,277 @@ impl Predator {
         initial_reserve: f64,
     ) -> Self {
         Predator {
+            id: 0,
+            especie: "Depredador".to_string(),
             min_reserve,
             opt_reserve,
             current_reserve: initial_reserve,
             sacrifice_age_days,
+            sacrificio: BTreeMap::new(),
+            enfermo: false,
+            consumido_hoy: 0.0,
+            initial_reserve,
+            age_days: 0,
+            dias_hambre: 0,
+            max_dias_hambre: 10,
+            edad_maxima: 15 * 365,
+            vivo: true,
+            padre: None,
+            dia_nacimiento: 0,
+            cria: None,
+            ultima_cria: None,
+            al_extinguirse: AlExtinguirse::default(),
+            estrategia: Rc::new(MasPesada),
+            max_cazas_por_dia: None,
+            max_intentos_por_dia: 10,
+            prob_exito: 1.0,
+            escape: BTreeMap::new(),
+            costo_fallo: 0.0,
+            cazas_hoy: 0,
+            intentos_hoy: 0,
+            capacidad_reserva: None,
+            deterioro_diario: 0.0,
+            masa_corporal: 0.0,
+            tasa_metabolica: 0.0,
+            costo_por_intento: 0.0,
+            perdido_hoy: 0.0,
+        }
+    }
+
+    // Un individuo nuevo con los mismos parámetros, la reserva inicial y edad cero.
+    pub fn reemplazo(&self, id: u32, dia: u32) -> Predator {
+        Predator {
+            id,
+            current_reserve: self.initial_reserve,
             enfermo: false,
             consumido_hoy: 0.0,
+            age_days: 0,
+            dias_hambre: 0,
+            vivo: true,
+            padre: None,
+            dia_nacimiento: dia,
+            ultima_cria: None,
+            ..self.clone()
         }
     }
 
+    // La camada del día, si este individuo está bien alimentado y en edad de criar.
+    pub fn criar(&mut self, rng: &mut dyn RngCore, ids: &mut GeneradorIds, dia: u32) -> Vec<Predator> {
+        let Some(cria) = self.cria.clone() else {
+            return Vec::new();
+        };
+        let descansado = self.ultima_cria.is_none_or(|ultima| dia >= ultima + cria.intervalo);
+        if !self.vivo || self.age_days < cria.edad_madurez || self.current_reserve < cria.reserva_minima || !descansado {
+            return Vec::new();
+        }
+
+        let camada = rng.gen_range(cria.camada_min..=cria.camada_max.max(cria.camada_min));
+        let mut cachorros = Vec::new();
+        for _ in 0..camada {
+            if self.current_reserve < cria.reserva_por_cria {
+                break;
+            }
+            self.current_reserve -= cria.reserva_por_cria;
+            let mut cachorro = self.reemplazo(ids.siguiente(), dia);
+            cachorro.current_reserve = cria.reserva_por_cria;
+            cachorro.padre = Some(self.id);
+            cachorros.push(cachorro);
+        }
+        if !cachorros.is_empty() {
+            self.ultima_cria = Some(dia);
+        }
+        cachorros
+    }
+
     pub fn current_reserve(&self) -> f64 {
         self.current_reserve
     }
 
+    pub fn envejecer(&mut self) {
+        self.age_days += 1;
+    }
+
     pub fn comenzar_dia(&mut self) {
         self.consumido_hoy = 0.0;
+        self.cazas_hoy = 0;
+        self.intentos_hoy = 0;
+
+        let excedente = self.capacidad_reserva.map_or(0.0, |cap| (self.current_reserve - cap).max(0.0));
+        let podrido = (self.current_reserve - excedente) * self.deterioro_diario;
+        self.perdido_hoy = excedente + podrido;
+        self.current_reserve -= self.perdido_hoy;
+    }
+
+    pub fn gasto_metabolico(&self) -> f64 {
+        self.tasa_metabolica * self.masa_corporal.powf(0.75) + self.costo_por_intento * self.intentos_hoy as f64
+    }
+
+    // Lo mínimo que tiene que comer hoy, entre lo cazado y la reserva.
+    pub fn necesidad_diaria(&self) -> f64 {
+        self.min_reserve + self.gasto_metabolico()
+    }
+
+    // Caza hasta acá; lo que sobre de una presa va a la reserva.
+    pub fn objetivo_diario(&self) -> f64 {
+        self.opt_reserve.max(self.necesidad_diaria())
+    }
+
+    // El esfuerzo sale de la reserva y, si no alcanza, de lo comido hoy.
+    fn gastar(&mut self, kg: f64) {
+        let de_reserva = kg.min(self.current_reserve);
+        self.current_reserve -= de_reserva;
+        self.consumido_hoy = (self.consumido_hoy - (kg - de_reserva)).max(0.0);
+    }
+
+    pub fn prob_exito_contra(&self, presa: &dyn Organismo, poblacion: &[Box<dyn Organismo>]) -> f64 {
+        let Some(escape) = presa.especie().and_then(|s| self.escape.get(&s)) else {
+            return self.prob_exito;
+        };
+        let grupo = poblacion
+            .iter()
+            .filter(|o| o.especie() == presa.especie() && o.grupo_contacto() == presa.grupo_contacto())
+            .count();
+        escape.prob_exito(self.prob_exito, presa, grupo)
+    }
+
+    pub fn puede_cazar(&self, presa: &dyn Organismo) -> bool {
+        let Some(especie) = presa.especie() else {
+            return false;
+        };
+        match self.sacrificio.get(&especie) {
+            Some(regla) => regla.admite(presa, self.sacrifice_age_days),
+            None => presa.edad().is_some_and(|e| e >= self.sacrifice_age_days),
+        }
+    }
+
+    pub fn quiere_cazar(&self) -> bool {
+        self.vivo
+            && self.consumido_hoy < self.objetivo_diario()
+            && self.max_cazas_por_dia.is_none_or(|max| self.cazas_hoy < max)
+            && self.intentos_hoy < self.max_intentos_por_dia
     }
 
     
-    pub fn chequear_al_final_del_dia(&mut self) {
-        if self.consumido_hoy < self.min_reserve {
-            let falta = self.min_reserve - self.consumido_hoy;
+    pub fn chequear_al_final_del_dia(&mut self, eventos: &mut Vec<Evento>) {
+        let necesidad = self.necesidad_diaria();
+        if self.consumido_hoy < necesidad {
+            let falta = necesidad - self.consumido_hoy;
             if self.current_reserve >= falta {
                 
                 self.current_reserve -= falta;
-                self.consumido_hoy = self.min_reserve;
-                println!(
-                    "Depredador usó {:.2} de reserva para alcanzar el mínimo. Reserva restante: {:.2}",
-                    falta, self.current_reserve
-                );
+                self.consumido_hoy = necesidad;
+                eventos.push(Evento::ReserveDrawn {
+                    predator_id: self.id,
+                    species: self.especie.clone(),
+                    amount: falta,
+                    remaining: self.current_reserve,
+                });
                 self.enfermo = false;
+                self.dias_hambre = 0;
             } else {
-                
+                // Come lo que queda en la reserva y pasa hambre.
                 self.enfermo = true;
-                println!(
-                    "Depredador no alcanzó el mínimo diario ({:.2} < {:.2}), enferma/muere.",
-                    self.consumido_hoy + self.current_reserve,
-                    self.min_reserve
-                );
+                self.dias_hambre += 1;
+                eventos.push(Evento::PredatorStarved {
+                    predator_id: self.id,
+                    species: self.especie.clone(),
+                    available: self.consumido_hoy + self.current_reserve,
+                    minimum: necesidad,
+                    days_starving: self.dias_hambre,
+                });
+                self.consumido_hoy += self.current_reserve;
+                self.current_reserve = 0.0;
             }
         } else {
             self.enfermo = false;
+            self.dias_hambre = 0;
+        }
+
+        let causa = if self.dias_hambre >= self.max_dias_hambre {
+            Some(CausaMuerte::Hambre)
+        } else if self.age_days >= self.edad_maxima {
+            Some(CausaMuerte::Vejez)
+        } else {
+            None
+        };
+        if let Some(cause) = causa {
+            self.vivo = false;
+            eventos.push(Evento::PredatorDied {
+                predator_id: self.id,
+                species: self.especie.clone(),
+                cause,
+                age_days: self.age_days,
+            });
         }
     }
 
     
-    pub fn cazar(&mut self, poblacion: &mut Vec<Box<dyn Organismo>>, rng: &mut ThreadRng) {
-        if self.consumido_hoy >= self.opt_reserve {
-            return;
+    // Un intento de caza. Devuelve si puede seguir cazando hoy: falso cuando ya no tiene hambre,
+    // agotó sus cazas o intentos, o no encontró presa.
+    pub fn cazar(
+        &mut self,
+        poblacion: &mut Vec<Box<dyn Organismo>>,
+        rng: &mut dyn RngCore,
+        eventos: &mut Vec<Evento>,
+    ) -> bool {
+        if !self.quiere_cazar() {
+            return false;
         }
 
         // Buscar candidatos
-        let candidatos: Vec<(usize, f64)> = poblacion
+        let candidatos: Vec<usize> = poblacion
             .iter()
             .enumerate()
-            .filter_map(|(i, ind)| {
-                if let Some(_especie) = ind.especie() {
-                    if let Some(edad_ind) = ind.edad() {
-                        if edad_ind >= self.sacrifice_age_days {
-                            return Some((i, ind.peso()));
-                        }
-                    }
-                }
-                None
-            })
+            .filter(|(_, ind)| self.puede_cazar(ind.as_ref()))
+            .map(|(i, _)| i)
             .collect();
-
         if candidatos.is_empty() {
-            return;
+            return false;
         }
 
-        
-        let max_peso = candidatos.iter().map(|(_, p)| *p).fold(f64::MIN, f64::max);
-        let mas_pesados: Vec<usize> = candidatos
-            .iter()
-            .filter(|(_, p)| (*p - max_peso).abs() < std::f64::EPSILON)
-            .map(|(i, _)| *i)
-            .collect();
-        let elegido_idx = *mas_pesados.choose(rng).unwrap();
+        let vistas: Vec<&dyn Organismo> = candidatos.iter().map(|&i| poblacion[i].as_ref()).collect();
+        let Some(elegido) = self.estrategia.elegir(&vistas, rng) else {
+            return false;
+        };
+        let elegido_idx = candidatos[elegido];
+
+        self.intentos_hoy += 1;
+        let probability = self.prob_exito_contra(poblacion[elegido_idx].as_ref(), poblacion);
+        if !probabilidad(probability, rng) {
+            self.gastar(self.costo_fallo);
+            if let (Some(prey_id), Some(species)) = (poblacion[elegido_idx].id(), poblacion[elegido_idx].especie()) {
+                eventos.push(Evento::HuntFailed { predator_id: self.id, prey_id, species, probability });
+            }
+            return self.quiere_cazar();
+        }
+        self.cazas_hoy += 1;
 
         let peso_pres = poblacion[elegido_idx].peso();
-        let faltar_para_optimo = self.opt_reserve - self.consumido_hoy;
-
-        if peso_pres <= faltar_para_optimo {
-            
-            self.consumido_hoy += peso_pres;
-            println!(
-                "Depredador cazó presa completa ({:.2}). Consumido hoy: {:.2}.",
-                peso_pres, self.consumido_hoy
-            );
+        let faltar_para_optimo = self.objetivo_diario() - self.consumido_hoy;
+
+        let (comido, guardado) = if peso_pres <= faltar_para_optimo {
+            (peso_pres, 0.0)
         } else {
-            
-            self.consumido_hoy += faltar_para_optimo;
-            let sobrante = peso_pres - faltar_para_optimo;
-            self.current_reserve += sobrante;
-            println!(
-                "Depredador cazó presa de {:.2}, consumió {:.2} y guardó {:.2} en reserva. \
-                 Consumido hoy: {:.2}, Reserva: {:.2}",
-                peso_pres,
-                faltar_para_optimo,
-                sobrante,
-                self.consumido_hoy,
-                self.current_reserve
-            );
-        }
+            (faltar_para_optimo, peso_pres - faltar_para_optimo)
+        };
+        let cabe = self.capacidad_reserva.map_or(f64::INFINITY, |cap| (cap - self.current_reserve).max(0.0));
+        let guardado = guardado.min(cabe);
+        self.perdido_hoy += peso_pres - comido - guardado;
+        self.consumido_hoy += comido;
+        self.current_reserve += guardado;
 
-        
-        poblacion.remove(elegido_idx);
+        let presa = poblacion.remove(elegido_idx);
+        if let (Some(prey_id), Some(species)) = (presa.id(), presa.especie()) {
+            eventos.push(Evento::Hunted {
+                predator_id: self.id,
+                prey_id,
+                species,
+                weight: peso_pres,
+                eaten: comido,
+                stored: guardado,
+                probability,
+            });
+        }
+        self.quiere_cazar()
     }
 }
-
-impl Organismo for Predator {
-    fn envejecer(&mut self) {}
-    fn reproducirse(&self, _rng: &mut ThreadRng, _poblacion: &Vec<Box<dyn Organismo>>) -> Vec<Box<dyn Organismo>> { Vec::new() }
-    fn peso(&self) -> f64 { 0.0 }
-    fn esta_vivo(&self) -> bool { !self.enfermo }
-    fn nombre(&self) -> &str { "Depredador" }
-    fn especie(&self) -> Option<Species> { None }
-    fn es_macho(&self) -> Option<bool> { None }
-    fn prob_enfermar(&self) -> Option<f64> { None }
-    fn prob_muerte_enfermedad(&self) -> Option<f64> { None }
-    fn tratar_enfermedad(&mut self, _rng: &mut ThreadRng) -> bool { false }
-    fn edad(&self) -> Option<u32> { None }
-}
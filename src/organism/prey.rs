@@ -1,17 +1,17 @@
+use crate::disease::{EstadoSalud, Progreso, RegistroEnfermedades};
+use crate::growth::CurvasCrecimiento;
+use crate::reproduction::Gestacion;
+use crate::sim::GeneradorIds;
+use crate::species::{Especie, Species};
 use crate::traits::Organismo;
-use crate::util::{crecimiento_gompertz}; 
-use rand::rngs::ThreadRng;
-use rand::Rng; 
+use crate::util::probabilidad;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::rc::Rc;
 
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum Species {
-    Cow,
-    Goat,
-    Rabbit,
-}
-
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
 pub enum Sex {
     Male,
     Female,
@@ -19,89 +19,162 @@ pub enum Sex {
 
 pub struct Prey {
     pub id: u32,
-    pub species: Species,
+    // Lo que comparte con su especie: curvas, ciclo reproductivo, consumo, edad máxima. Las crías
+    // heredan la de la madre.
+    pub especie: Rc<Especie>,
     pub sex: Sex,
     pub age_days: u32,
     pub weight_kg: f64,
-    growth_fn: Box<dyn Fn(u32) -> f64>,
-    pub prob_enfermar: f64,
-    pub prob_muerte_enfermedad: f64,
-    pub enfermo: bool,
+    // El mayor peso que alcanzó; contra él se mide cuánto adelgazó.
+    pub peso_maximo: f64,
+    // Estado frente a cada enfermedad del registro; las que no figuran están en Susceptible.
+    pub salud: BTreeMap<usize, EstadoSalud>,
+    pub grupo_contacto: u32,
+    pub aislado: bool,
+    pub madre: Option<u32>,
+    pub padre: Option<u32>,
+    pub dia_nacimiento: u32,
+    pub temporada_apareamiento: u32,
+    pub apareamientos_en_temporada: u32,
+    pub gestacion: Option<Gestacion>,
+    pub ultimo_parto: Option<u32>,
+    // Fracción de su demanda de pasto que comió ayer; sin pastura siempre es la ración completa.
+    pub racion: f64,
+    pub dias_hambre: u32,
 }
 
 impl Prey {
     pub fn new(
         id: u32,
-        species: Species,
+        especie: &Rc<Especie>,
         sex: Sex,
-        prob_enfermar: f64,
-        prob_muerte_enfermedad: f64,
     ) -> Self {
-        let (a, b, k) = match species {
-            Species::Cow => (700.0, 3.0, 0.008),
-            Species::Goat => (75.0, 2.8, 0.01),
-            Species::Rabbit => (5.0, 2.5, 0.05),
-        };
-        let growth = Box::new(crecimiento_gompertz(a, b, k));
-        let weight = growth(0);
+        let weight = especie.curvas.de(sex).peso(0);
         Prey {
             id,
-            species,
+            especie: especie.clone(),
             sex,
             age_days: 0,
             weight_kg: weight,
-            growth_fn: growth,
-            prob_enfermar,
-            prob_muerte_enfermedad,
-            enfermo: false,
+            peso_maximo: weight,
+            salud: BTreeMap::new(),
+            grupo_contacto: 0,
+            aislado: false,
+            madre: None,
+            padre: None,
+            dia_nacimiento: 0,
+            temporada_apareamiento: 0,
+            apareamientos_en_temporada: 0,
+            gestacion: None,
+            ultimo_parto: None,
+            racion: 1.0,
+            dias_hambre: 0,
         }
     }
+
+    // Cambia las curvas de crecimiento y lleva el peso al de la nueva curva para su edad. El resto
+    // de la especie no cambia: este animal pasa a tener su propia copia, que heredan sus crías.
+    pub fn set_curvas(&mut self, curvas: CurvasCrecimiento) {
+        Rc::make_mut(&mut self.especie).curvas = curvas;
+        self.weight_kg = self.potencial(self.age_days);
+        self.peso_maximo = self.weight_kg;
+    }
+
+    fn potencial(&self, edad: u32) -> f64 {
+        self.especie.curvas.de(self.sex).peso(edad)
+    }
+
+    // Peso sobre el mayor peso que alcanzó: 1 es un animal que no adelgazó. Una cría que creció
+    // menos de lo que podía no está flaca por eso.
+    pub fn condicion_corporal(&self) -> f64 {
+        if self.peso_maximo > 0.0 { self.weight_kg / self.peso_maximo } else { 1.0 }
+    }
+
+    // Peso sobre el de la curva para su edad: cuánto creció de lo que podía.
+    pub fn desarrollo(&self) -> f64 {
+        let potencial = self.potencial(self.age_days);
+        if potencial > 0.0 { self.weight_kg / potencial } else { 1.0 }
+    }
 }
 
 impl Organismo for Prey {
+    // La curva de crecimiento es el techo; cuánto se acerca a ella depende de lo que comió ayer y de
+    // si está enfermo.
     fn envejecer(&mut self) {
+        let potencial_ayer = self.potencial(self.age_days);
         self.age_days += 1;
-        
-        self.weight_kg = (self.growth_fn)(self.age_days);
+        let potencial_hoy = self.potencial(self.age_days);
+        let ingesta = self.especie.consumo.ingesta(self.racion, self.esta_enfermo());
+        if ingesta < self.especie.consumo.mantenimiento {
+            self.dias_hambre += 1;
+        } else {
+            self.dias_hambre = 0;
+        }
+        self.weight_kg = self.especie.consumo.crecer(self.weight_kg, potencial_ayer, potencial_hoy, ingesta);
+        self.peso_maximo = self.peso_maximo.max(self.weight_kg);
     }
 
-    fn reproducirse(
-        &self,
-        rng: &mut ThreadRng,
-        poblacion: &Vec<Box<dyn Organismo>>
-    ) -> Vec<Box<dyn Organismo>> {
-        let mut hijos: Vec<Box<dyn Organismo>> = Vec::new();
+    fn en_celo(&self, rng: &mut dyn RngCore, dia: u32) -> bool {
+        self.sex == Sex::Female
+            && !self.aislado
+            && self.gestacion.is_none()
+            && self.condicion_corporal() >= self.especie.consumo.condicion_celo
+            && self.desarrollo() >= self.especie.consumo.desarrollo_celo
+            && self.especie.reproduccion.puede_concebir(self.age_days, self.ultimo_parto, dia)
+            && self.especie.reproduccion.entra_en_celo(rng)
+    }
 
-        
-        let existe_macho = poblacion.iter().any(|ind| {
-            if let (Some(especie_i), Some(es_macho_i)) = (ind.especie(), ind.es_macho()) {
-                especie_i == self.species && es_macho_i
-            } else {
-                false
-            }
-        });
+    fn puede_aparearse(&self, temporada: u32, capacidad: u32) -> bool {
+        // Lo que cubrió en otra temporada no cuenta para esta.
+        let usados = if self.temporada_apareamiento == temporada { self.apareamientos_en_temporada } else { 0 };
+        self.sex == Sex::Male
+            && !self.aislado
+            && self.age_days >= self.especie.reproduccion.edad_madurez
+            && usados < capacidad
+    }
 
-        if !existe_macho {
-            return hijos;
+    fn registrar_apareamiento(&mut self, temporada: u32) {
+        if self.temporada_apareamiento != temporada {
+            self.temporada_apareamiento = temporada;
+            self.apareamientos_en_temporada = 0;
         }
+        self.apareamientos_en_temporada += 1;
+    }
 
-        
-        if self.sex == Sex::Female && self.age_days > 180 && rng.gen_bool(0.01) {
-            let num_offspring = match self.species {
-                Species::Cow => 1,
-                Species::Goat => rng.gen_range(1..=3),
-                Species::Rabbit => rng.gen_range(3..=8),
-            };
-            for i in 0..num_offspring {
-                let sex = if rng.gen_bool(0.5) { Sex::Male } else { Sex::Female };
-                hijos.push(Box::new( Prey::new(
-                    self.id + 1000 + i,
-                    self.species,
-                    sex,
-                    self.prob_enfermar,
-                    self.prob_muerte_enfermedad,
-                )));
-            }
+    fn concebir(&mut self, padre: u32, dia: u32) {
+        self.gestacion = Some(Gestacion {
+            padre,
+            dia_concepcion: dia,
+            dia_parto: dia + self.especie.reproduccion.gestacion_dias,
+        });
+    }
+
+    fn gestacion(&self) -> Option<Gestacion> {
+        self.gestacion
+    }
+
+    fn reproducirse(
+        &mut self,
+        rng: &mut dyn RngCore,
+        ids: &mut GeneradorIds,
+        dia: u32,
+    ) -> Vec<Box<dyn Organismo>> {
+        let Some(gestacion) = self.gestacion.filter(|g| dia >= g.dia_parto) else {
+            return Vec::new();
+        };
+        self.gestacion = None;
+        self.ultimo_parto = Some(dia);
+
+        let num_offspring = self.especie.reproduccion.tamano_camada(rng);
+        let mut hijos: Vec<Box<dyn Organismo>> = Vec::new();
+        for _ in 0..num_offspring {
+            let sex = if probabilidad(0.5, rng) { Sex::Male } else { Sex::Female };
+            let mut cria = Prey::new(ids.siguiente(), &self.especie, sex);
+            cria.madre = Some(self.id);
+            cria.padre = Some(gestacion.padre);
+            cria.dia_nacimiento = dia;
+            cria.grupo_contacto = self.grupo_contacto;
+            hijos.push(Box::new(cria));
         }
 
         hijos
@@ -111,61 +184,113 @@ impl Organismo for Prey {
         self.weight_kg
     }
 
+    // Enfermo o sano, nadie pasa de la edad máxima de su especie.
     fn esta_vivo(&self) -> bool {
-        
-        let vivo_por_edad = match self.species {
-            Species::Cow => self.age_days < 25 * 365,
-            Species::Goat => self.age_days < 15 * 365,
-            Species::Rabbit => self.age_days < 8 * 365,
-        };
-
-        
-        if self.enfermo {
-            true
-        } else {
-            vivo_por_edad
-        }
+        self.age_days < self.especie.edad_maxima
     }
 
     fn nombre(&self) -> &str {
-        match self.species {
-            Species::Cow => "Vaca",
-            Species::Goat => "Cabra",
-            Species::Rabbit => "Conejo",
-        }
+        self.especie.species.nombre()
     }
 
     fn especie(&self) -> Option<Species> {
-        Some(self.species)
+        Some(self.especie.species.clone())
     }
 
     fn es_macho(&self) -> Option<bool> {
         Some(self.sex == Sex::Male)
     }
 
-    fn prob_enfermar(&self) -> Option<f64> {
-        Some(self.prob_enfermar)
+    fn avanzar_enfermedades(
+        &mut self,
+        rng: &mut dyn RngCore,
+        enfermedades: &RegistroEnfermedades,
+        presiones: &[f64],
+    ) -> Vec<(usize, Progreso)> {
+        let mut cambios = Vec::new();
+        let riesgo = self.especie.susceptibilidad * self.especie.consumo.riesgo_enfermedad(self.condicion_corporal());
+        for (idx, enfermedad) in enfermedades.iter() {
+            let Some(huesped) = enfermedad.huesped(&self.especie.species) else {
+                continue;
+            };
+            let estado = self.salud.entry(idx).or_insert(EstadoSalud::Susceptible);
+            let progreso = huesped.avanzar(estado, presiones.get(idx).copied().unwrap_or(0.0), riesgo, rng);
+            if progreso != Progreso::SinCambios {
+                cambios.push((idx, progreso));
+            }
+            if progreso == Progreso::Murio {
+                break;
+            }
+        }
+        cambios
     }
 
-    fn prob_muerte_enfermedad(&self) -> Option<f64> {
-        Some(self.prob_muerte_enfermedad)
+    fn salud(&self, enfermedad: usize) -> Option<EstadoSalud> {
+        Some(self.salud.get(&enfermedad).copied().unwrap_or(EstadoSalud::Susceptible))
     }
 
-    fn tratar_enfermedad(&mut self, rng: &mut ThreadRng) -> bool {
-        if self.enfermo {
-            if rng.gen_bool(self.prob_muerte_enfermedad) {
-                return true;  
-            } else {
-                return false; 
-            }
-        } else {
-            if rng.gen_bool(self.prob_enfermar) {
-                self.enfermo = true;
-            }
-            return false;
-        }
+    fn set_salud(&mut self, enfermedad: usize, estado: EstadoSalud) {
+        self.salud.insert(enfermedad, estado);
+    }
+
+    fn contagia(&self, enfermedad: usize) -> bool {
+        !self.aislado && self.salud.get(&enfermedad) == Some(&EstadoSalud::Enfermo)
     }
+
+    fn esta_enfermo(&self) -> bool {
+        self.salud.values().any(|e| *e == EstadoSalud::Enfermo)
+    }
+
+    fn aislado(&self) -> bool {
+        self.aislado
+    }
+
+    fn set_aislado(&mut self, aislado: bool) {
+        self.aislado = aislado;
+    }
+
+    fn grupo_contacto(&self) -> Option<u32> {
+        Some(self.grupo_contacto)
+    }
+
+    fn demanda_forraje(&self) -> Option<f64> {
+        Some(self.especie.consumo.fraccion_peso * self.weight_kg)
+    }
+
+    fn comer(&mut self, kg: f64) {
+        let demanda = self.especie.consumo.fraccion_peso * self.weight_kg;
+        self.racion = if demanda > 0.0 { kg / demanda } else { 1.0 };
+    }
+
+    fn dias_hambre(&self) -> Option<u32> {
+        Some(self.dias_hambre)
+    }
+
+    fn muere_de_hambre(&self) -> bool {
+        self.condicion_corporal() < self.especie.consumo.condicion_minima
+    }
+
+    fn condicion(&self) -> Option<f64> {
+        Some(self.condicion_corporal())
+    }
+
     fn edad(&self) -> Option<u32> {
         Some(self.age_days)
     }
+
+    fn id(&self) -> Option<u32> {
+        Some(self.id)
+    }
+
+    fn madre(&self) -> Option<u32> {
+        self.madre
+    }
+
+    fn padre(&self) -> Option<u32> {
+        self.padre
+    }
+
+    fn dia_nacimiento(&self) -> Option<u32> {
+        Some(self.dia_nacimiento)
+    }
 }
@@ -0,0 +1,225 @@
+use crate::species::Species;
+use crate::traits::Organismo;
+use crate::util::{validar_no_negativo, validar_probabilidad};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// Cuánto pasto come una especie y qué le pasa según lo que come. Pide por día `fraccion_peso` de
+// su peso, en kg de materia seca. La curva de crecimiento de la especie es el peso máximo posible
+// para la edad; la condición corporal es el peso sobre el mayor peso que alcanzó el animal.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ConsumoForraje {
+    pub fraccion_peso: f64,
+    // Fracción de la ración que solo sostiene el peso: con menos adelgaza, con más crece.
+    #[serde(default = "mantenimiento_por_defecto")]
+    pub mantenimiento: f64,
+    // Fracción del peso que puede recuperar por día, además de lo que crece la curva.
+    #[serde(default = "tasa_recuperacion_por_defecto")]
+    pub tasa_recuperacion: f64,
+    // Fracción del peso que pierde por día sin comer nada.
+    #[serde(default = "tasa_perdida_por_defecto")]
+    pub tasa_perdida: f64,
+    // Cuánto de lo que come no aprovecha mientras está enfermo.
+    #[serde(default = "penalizacion_enfermo_por_defecto")]
+    pub penalizacion_enfermo: f64,
+    // Por debajo de esta condición las hembras no entran en celo.
+    #[serde(default = "condicion_celo_por_defecto")]
+    pub condicion_celo: f64,
+    // Tampoco entran en celo si no llegan a esta fracción del peso de la curva para su edad: una
+    // cría que no creció no se preña aunque tenga la edad.
+    #[serde(default = "desarrollo_celo_por_defecto")]
+    pub desarrollo_celo: f64,
+    // Por debajo de esta condición muere de hambre.
+    #[serde(default = "condicion_minima_por_defecto")]
+    pub condicion_minima: f64,
+    // El riesgo de contagio se multiplica por `1 + sensibilidad_enfermedad * (1 - condición)`.
+    #[serde(default = "sensibilidad_enfermedad_por_defecto")]
+    pub sensibilidad_enfermedad: f64,
+}
+
+fn mantenimiento_por_defecto() -> f64 {
+    0.6
+}
+
+fn tasa_recuperacion_por_defecto() -> f64 {
+    0.01
+}
+
+fn tasa_perdida_por_defecto() -> f64 {
+    0.01
+}
+
+fn penalizacion_enfermo_por_defecto() -> f64 {
+    0.5
+}
+
+fn condicion_celo_por_defecto() -> f64 {
+    0.8
+}
+
+fn desarrollo_celo_por_defecto() -> f64 {
+    0.7
+}
+
+fn condicion_minima_por_defecto() -> f64 {
+    0.6
+}
+
+fn sensibilidad_enfermedad_por_defecto() -> f64 {
+    2.0
+}
+
+impl ConsumoForraje {
+    // Las fracciones van entre 0 y 1; las condiciones y tasas, aunque pasen de 1, no son negativas.
+    pub fn validar(&self) -> Result<(), String> {
+        validar_no_negativo("fraccion_peso", self.fraccion_peso)?;
+        validar_probabilidad("mantenimiento", self.mantenimiento)?;
+        validar_no_negativo("tasa_recuperacion", self.tasa_recuperacion)?;
+        validar_probabilidad("tasa_perdida", self.tasa_perdida)?;
+        validar_probabilidad("penalizacion_enfermo", self.penalizacion_enfermo)?;
+        validar_no_negativo("condicion_celo", self.condicion_celo)?;
+        validar_no_negativo("desarrollo_celo", self.desarrollo_celo)?;
+        validar_no_negativo("condicion_minima", self.condicion_minima)?;
+        validar_no_negativo("sensibilidad_enfermedad", self.sensibilidad_enfermedad)
+    }
+
+    // Lo que aprovecha de la ración de ayer, entre 0 y 1.
+    pub fn ingesta(&self, racion: f64, enfermo: bool) -> f64 {
+        let ingesta = racion.clamp(0.0, 1.0);
+        if enfermo { ingesta * (1.0 - self.penalizacion_enfermo.clamp(0.0, 1.0)) } else { ingesta }
+    }
+
+    // El peso de hoy. Se lleva como déficit bajo la curva: con la ración completa el déficit se
+    // cierra a razón de lo que crece la curva más `tasa_recuperacion`, así un animal bien comido
+    // sigue la curva exacta; por debajo de `mantenimiento` el déficit crece.
+    pub fn crecer(&self, peso: f64, potencial_ayer: f64, potencial_hoy: f64, ingesta: f64) -> f64 {
+        // Una curva armada a mano, sin pasar por el escenario, puede dar un potencial negativo.
+        let potencial_hoy = potencial_hoy.max(0.0);
+        let crecimiento = (potencial_hoy - potencial_ayer).max(0.0);
+        let mut deficit = (potencial_hoy - peso).max(0.0);
+        let mantenimiento = self.mantenimiento.clamp(0.0, 1.0);
+        if ingesta >= mantenimiento {
+            let aprovechado = if mantenimiento < 1.0 { (ingesta - mantenimiento) / (1.0 - mantenimiento) } else { 1.0 };
+            deficit -= aprovechado * (crecimiento + self.tasa_recuperacion * peso);
+        } else {
+            deficit += (mantenimiento - ingesta) / mantenimiento * self.tasa_perdida * peso;
+        }
+        potencial_hoy - deficit.clamp(0.0, potencial_hoy)
+    }
+
+    pub fn riesgo_enfermedad(&self, condicion: f64) -> f64 {
+        1.0 + self.sensibilidad_enfermedad * (1.0 - condicion).max(0.0)
+    }
+}
+
+// Cómo se reparte el pasto cuando no alcanza para todos.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Competencia {
+    // Cada uno recibe la misma fracción de lo que pide.
+    #[default]
+    Proporcional,
+    // Comen primero los más pesados, hasta llenarse, y los últimos se quedan con lo que sobra.
+    PorPeso,
+}
+
+// El pasto que comparten todas las especies, en kg de materia seca. Rebrota de forma logística
+// hacia `capacidad` a `tasa_rebrote` por día; la fracción `remanente` de la capacidad queda siempre
+// al ras del suelo y no se puede comer, así el pasto se recupera aunque lo arrasen.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ParametrosPastura {
+    pub capacidad: f64,
+    pub tasa_rebrote: f64,
+    // Sin valor, la pastura empieza llena.
+    #[serde(default)]
+    pub biomasa_inicial: Option<f64>,
+    #[serde(default = "remanente_por_defecto")]
+    pub remanente: f64,
+    #[serde(default)]
+    pub competencia: Competencia,
+}
+
+fn remanente_por_defecto() -> f64 {
+    0.05
+}
+
+impl ParametrosPastura {
+    pub fn validar(&self) -> Result<(), String> {
+        validar_no_negativo("pastura.capacidad", self.capacidad)?;
+        validar_no_negativo("pastura.tasa_rebrote", self.tasa_rebrote)?;
+        if let Some(biomasa) = self.biomasa_inicial {
+            validar_no_negativo("pastura.biomasa_inicial", biomasa)?;
+        }
+        validar_probabilidad("pastura.remanente", self.remanente)
+    }
+}
+
+// Lo que pasó en la pastura en un día, por especie.
+#[derive(Debug, Clone, Default)]
+pub struct Pastoreo {
+    pub biomasa: f64,
+    pub demandado: BTreeMap<Species, f64>,
+    pub comido: BTreeMap<Species, f64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Pastura {
+    pub parametros: ParametrosPastura,
+    pub biomasa: f64,
+}
+
+impl Pastura {
+    pub fn new(parametros: ParametrosPastura) -> Self {
+        let biomasa = parametros.biomasa_inicial.unwrap_or(parametros.capacidad);
+        Pastura { parametros, biomasa }
+    }
+
+    fn remanente(&self) -> f64 {
+        self.parametros.remanente.clamp(0.0, 1.0) * self.parametros.capacidad
+    }
+
+    // Come cada animal según su demanda y al final rebrota lo que quedó.
+    pub fn pastorear(&mut self, animals: &mut [Box<dyn Organismo>]) -> Pastoreo {
+        let mut pastoreo = Pastoreo::default();
+        let demandas: Vec<f64> = animals.iter().map(|a| a.demanda_forraje().unwrap_or(0.0)).collect();
+        let total: f64 = demandas.iter().sum();
+        let disponible = (self.biomasa - self.remanente()).max(0.0);
+
+        let mut comido = vec![0.0; animals.len()];
+        match self.parametros.competencia {
+            _ if total <= disponible => comido.clone_from(&demandas),
+            Competencia::Proporcional => {
+                for (c, d) in comido.iter_mut().zip(&demandas) {
+                    *c = d * disponible / total;
+                }
+            }
+            Competencia::PorPeso => {
+                let mut orden: Vec<usize> = (0..animals.len()).collect();
+                orden.sort_by(|&i, &j| animals[j].peso().total_cmp(&animals[i].peso()));
+                let mut queda = disponible;
+                for i in orden {
+                    comido[i] = demandas[i].min(queda);
+                    queda -= comido[i];
+                }
+            }
+        }
+
+        for (i, a) in animals.iter_mut().enumerate() {
+            let Some(species) = a.especie() else {
+                continue;
+            };
+            a.comer(comido[i]);
+            *pastoreo.demandado.entry(species.clone()).or_insert(0.0) += demandas[i];
+            *pastoreo.comido.entry(species).or_insert(0.0) += comido[i];
+            self.biomasa -= comido[i];
+        }
+
+        let p = &self.parametros;
+        if p.capacidad > 0.0 {
+            self.biomasa += p.tasa_rebrote * self.biomasa * (1.0 - self.biomasa / p.capacidad);
+        }
+        self.biomasa = self.biomasa.clamp(self.remanente().min(p.capacidad), p.capacidad.max(0.0));
+        pastoreo.biomasa = self.biomasa;
+        pastoreo
+    }
+}
@@ -0,0 +1,82 @@
+use crate::util::{probabilidad, validar_probabilidad};
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+
+// Ciclo reproductivo de una especie. Machos y hembras maduran a la misma edad.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ParametrosReproductivos {
+    pub edad_madurez: u32,
+    pub gestacion_dias: u32,
+    pub lactancia_dias: u32,
+    // Días mínimos entre dos partos seguidos.
+    pub intervalo_entre_partos: u32,
+    // Probabilidad diaria de que una hembra apta entre en celo.
+    pub prob_celo: f64,
+    pub camada_min: u32,
+    pub camada_max: u32,
+    // Pesos relativos de cada tamaño de camada desde `camada_min`; vacío es uniforme.
+    #[serde(default)]
+    pub camada_pesos: Vec<f64>,
+}
+
+impl ParametrosReproductivos {
+    pub fn validar(&self) -> Result<(), String> {
+        validar_probabilidad("prob_celo", self.prob_celo)?;
+        if self.camada_min > self.camada_max {
+            return Err(format!("camada_min ({}) es mayor que camada_max ({})", self.camada_min, self.camada_max));
+        }
+        let tamanos = (self.camada_max - self.camada_min) as usize + 1;
+        if !self.camada_pesos.is_empty() && self.camada_pesos.len() != tamanos {
+            return Err(format!(
+                "camada_pesos tiene {} pesos y hay {} tamaños de camada entre {} y {}",
+                self.camada_pesos.len(),
+                tamanos,
+                self.camada_min,
+                self.camada_max
+            ));
+        }
+        if self.camada_pesos.iter().any(|&p| !(p >= 0.0 && p.is_finite())) {
+            return Err("camada_pesos no puede tener pesos negativos".to_string());
+        }
+        if !self.camada_pesos.is_empty() && self.camada_pesos.iter().all(|&p| p == 0.0) {
+            return Err("camada_pesos tiene todos los pesos en cero".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn tamano_camada(&self, rng: &mut dyn RngCore) -> u32 {
+        let max = self.camada_max.max(self.camada_min);
+        if let Ok(dist) = WeightedIndex::new(&self.camada_pesos) {
+            return (self.camada_min + dist.sample(rng) as u32).min(max);
+        }
+        rng.gen_range(self.camada_min..=max)
+    }
+
+    // Una hembra que ya parió no vuelve a entrar en celo durante la lactancia, ni tan pronto
+    // que el próximo parto quede antes del intervalo mínimo.
+    pub fn puede_concebir(&self, edad: u32, ultimo_parto: Option<u32>, dia: u32) -> bool {
+        if edad < self.edad_madurez {
+            return false;
+        }
+        match ultimo_parto {
+            Some(parto) => {
+                let desde_parto = dia.saturating_sub(parto);
+                desde_parto >= self.lactancia_dias
+                    && desde_parto + self.gestacion_dias >= self.intervalo_entre_partos
+            }
+            None => true,
+        }
+    }
+
+    pub fn entra_en_celo(&self, rng: &mut dyn RngCore) -> bool {
+        probabilidad(self.prob_celo, rng)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Gestacion {
+    pub padre: u32,
+    pub dia_concepcion: u32,
+    pub dia_parto: u32,
+}
@@ -0,0 +1,391 @@
+use crate::disease::{Enfermedad, RegistroEnfermedades};
+use crate::growth::{CrecimientoEspecie, ModeloCrecimiento};
+use crate::hunting::{Escape, EstrategiaCaza, ReglaSacrificio};
+use crate::mating::ConfigApareamiento;
+use crate::organism::predator::{AlExtinguirse, ParametrosCria, Predator};
+use crate::organism::prey::{Prey, Sex};
+use crate::pasture::{ConsumoForraje, ParametrosPastura, Pastura};
+use crate::reproduction::ParametrosReproductivos;
+use crate::sim::Simulation;
+use crate::species::{self, DefinicionEspecie, RegistroEspecies, Species};
+use crate::traits::Organismo;
+use crate::util::{validar_no_negativo, validar_probabilidad};
+use crate::veterinary::{Intervencion, PlanSanitario};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+const ESCENARIO_POR_DEFECTO: &str = include_str!("../escenarios/default.toml");
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Scenario {
+    #[serde(default = "dias_por_defecto")]
+    pub dias: u32,
+    #[serde(default)]
+    pub seed: Option<u64>,
+    // Forma corta para un solo depredador; se suma a los de `depredadores`.
+    #[serde(default)]
+    pub predator: Option<PredatorParams>,
+    #[serde(default)]
+    pub depredadores: Vec<PredatorParams>,
+    pub poblacion: Vec<GrupoInicial>,
+    // Especies de presa que se suman a las de fábrica, o las reemplazan si usan la misma clave.
+    #[serde(default)]
+    pub especies: Vec<DefinicionEspecie>,
+    #[serde(default)]
+    pub apareamiento: ConfigApareamiento,
+    // Reemplaza el ciclo reproductivo de las especies listadas.
+    #[serde(default)]
+    pub reproduccion: BTreeMap<Species, ParametrosReproductivos>,
+    #[serde(default)]
+    pub enfermedades: Vec<Enfermedad>,
+    #[serde(default)]
+    pub intervenciones: Vec<Intervencion>,
+    // Sin pastura los animales no comen.
+    #[serde(default)]
+    pub pastura: Option<ParametrosPastura>,
+    // Reemplaza el consumo de pasto de las especies listadas.
+    #[serde(default)]
+    pub consumo: BTreeMap<Species, ConsumoForraje>,
+    // Reemplaza las curvas de crecimiento de las especies listadas; un sexo sin curva propia sigue
+    // con la de la especie.
+    #[serde(default)]
+    pub crecimiento: BTreeMap<Species, CrecimientoEspecie>,
+    // Las curvas que se ajustaron a pesajes al cargar el escenario, con la especie y el sexo al que
+    // se aplican.
+    #[serde(skip)]
+    pub curvas_ajustadas: Vec<(String, ModeloCrecimiento)>,
+}
+
+// Un grupo de depredadores iguales; grupos con el mismo `especie` forman una misma especie.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PredatorParams {
+    #[serde(default = "especie_depredador_por_defecto")]
+    pub especie: String,
+    #[serde(default = "cantidad_por_defecto")]
+    pub cantidad: u32,
+    pub min_reserve: f64,
+    pub opt_reserve: f64,
+    pub sacrifice_age_days: u32,
+    #[serde(default)]
+    pub sacrificio: BTreeMap<Species, ReglaSacrificio>,
+    pub initial_reserve: f64,
+    #[serde(default = "max_dias_hambre_por_defecto")]
+    pub max_dias_hambre: u32,
+    #[serde(default = "edad_maxima_depredador_por_defecto")]
+    pub edad_maxima: u32,
+    #[serde(default)]
+    pub cria: Option<ParametrosCria>,
+    #[serde(default)]
+    pub al_extinguirse: AlExtinguirse,
+    #[serde(default)]
+    pub estrategia: EstrategiaCaza,
+    #[serde(default)]
+    pub max_cazas_por_dia: Option<u32>,
+    #[serde(default = "max_intentos_por_dia_por_defecto")]
+    pub max_intentos_por_dia: u32,
+    #[serde(default = "prob_exito_por_defecto")]
+    pub prob_exito: f64,
+    #[serde(default)]
+    pub escape: BTreeMap<Species, Escape>,
+    #[serde(default)]
+    pub costo_fallo: f64,
+    #[serde(default)]
+    pub capacidad_reserva: Option<f64>,
+    #[serde(default)]
+    pub deterioro_diario: f64,
+    #[serde(default)]
+    pub masa_corporal: f64,
+    #[serde(default)]
+    pub tasa_metabolica: f64,
+    #[serde(default)]
+    pub costo_por_intento: f64,
+}
+
+// Un grupo de individuos iguales; `cantidad = 1` describe a un individuo suelto.
+#[derive(Debug, Clone, Deserialize)]
+pub struct GrupoInicial {
+    pub species: Species,
+    pub sex: Sex,
+    #[serde(default = "cantidad_por_defecto")]
+    pub cantidad: u32,
+    #[serde(default)]
+    pub grupo_contacto: u32,
+}
+
+impl PredatorParams {
+    fn validar(&self) -> Result<(), String> {
+        let campo = |nombre: &str| format!("{} de {}", nombre, self.especie);
+        validar_probabilidad(&campo("prob_exito"), self.prob_exito)?;
+        for (species, escape) in &self.escape {
+            if let Some(p) = escape.prob_exito {
+                validar_probabilidad(&campo(&format!("escape.{}.prob_exito", species.clave())), p)?;
+            }
+        }
+        validar_no_negativo(&campo("costo_fallo"), self.costo_fallo)?;
+        validar_no_negativo(&campo("costo_por_intento"), self.costo_por_intento)?;
+        validar_no_negativo(&campo("min_reserve"), self.min_reserve)?;
+        validar_no_negativo(&campo("opt_reserve"), self.opt_reserve)?;
+        validar_no_negativo(&campo("initial_reserve"), self.initial_reserve)?;
+        if let Some(capacidad) = self.capacidad_reserva {
+            validar_no_negativo(&campo("capacidad_reserva"), capacidad)?;
+        }
+        validar_probabilidad(&campo("deterioro_diario"), self.deterioro_diario)?;
+        validar_no_negativo(&campo("masa_corporal"), self.masa_corporal)?;
+        validar_no_negativo(&campo("tasa_metabolica"), self.tasa_metabolica)?;
+        if let Some(cria) = &self.cria {
+            validar_no_negativo(&campo("cria.reserva_minima"), cria.reserva_minima)?;
+            validar_no_negativo(&campo("cria.reserva_por_cria"), cria.reserva_por_cria)?;
+            if cria.camada_min > cria.camada_max {
+                return Err(format!(
+                    "cria.camada_min ({}) es mayor que cria.camada_max ({}) en {}",
+                    cria.camada_min, cria.camada_max, self.especie
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn dias_por_defecto() -> u32 {
+    500
+}
+
+fn cantidad_por_defecto() -> u32 {
+    1
+}
+
+fn especie_depredador_por_defecto() -> String {
+    "Depredador".to_string()
+}
+
+fn max_intentos_por_dia_por_defecto() -> u32 {
+    10
+}
+
+fn prob_exito_por_defecto() -> f64 {
+    1.0
+}
+
+fn max_dias_hambre_por_defecto() -> u32 {
+    10
+}
+
+fn edad_maxima_depredador_por_defecto() -> u32 {
+    15 * 365
+}
+
+#[derive(Debug)]
+pub enum ScenarioError {
+    Io(std::io::Error),
+    Toml(toml::de::Error),
+    Json(serde_json::Error),
+    Invalido(String),
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScenarioError::Io(e) => write!(f, "no se pudo leer el escenario: {}", e),
+            ScenarioError::Toml(e) => write!(f, "escenario TOML inválido: {}", e),
+            ScenarioError::Json(e) => write!(f, "escenario JSON inválido: {}", e),
+            ScenarioError::Invalido(motivo) => write!(f, "escenario inválido: {}", motivo),
+        }
+    }
+}
+
+impl std::error::Error for ScenarioError {}
+
+impl Scenario {
+    // El formato se elige por la extensión: `.json` es JSON, cualquier otra cosa TOML.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ScenarioError> {
+        let path = path.as_ref();
+        let texto = std::fs::read_to_string(path).map_err(ScenarioError::Io)?;
+        if path.extension().is_some_and(|ext| ext == "json") {
+            Self::from_json_str(&texto)
+        } else {
+            Self::from_toml_str(&texto)
+        }
+    }
+
+    pub fn from_toml_str(texto: &str) -> Result<Self, ScenarioError> {
+        let mut escenario: Scenario = toml::from_str(texto).map_err(ScenarioError::Toml)?;
+        escenario.validar()?;
+        escenario.ajustar_curvas();
+        Ok(escenario)
+    }
+
+    pub fn from_json_str(texto: &str) -> Result<Self, ScenarioError> {
+        let mut escenario: Scenario = serde_json::from_str(texto).map_err(ScenarioError::Json)?;
+        escenario.validar()?;
+        escenario.ajustar_curvas();
+        Ok(escenario)
+    }
+
+    // Las especies de presa de la corrida: las de fábrica, las de `[[especies]]` y encima los
+    // reemplazos de `reproduccion`, `consumo` y `crecimiento`.
+    pub fn definiciones_especies(&self) -> Vec<DefinicionEspecie> {
+        let mut definiciones = species::definiciones_por_defecto();
+        for propia in &self.especies {
+            match definiciones.iter_mut().find(|d| d.clave == propia.clave) {
+                Some(d) => *d = propia.clone(),
+                None => definiciones.push(propia.clone()),
+            }
+        }
+        for d in &mut definiciones {
+            let species = d.species();
+            if let Some(reproduccion) = self.reproduccion.get(&species) {
+                d.reproduccion = reproduccion.clone();
+            }
+            if let Some(consumo) = self.consumo.get(&species) {
+                d.consumo = consumo.clone();
+            }
+            if let Some(crecimiento) = self.crecimiento.get(&species) {
+                d.crecimiento = crecimiento.sobre(&d.crecimiento);
+            }
+        }
+        definiciones
+    }
+
+    // Lo que el formato no puede comprobar: que cada especie esté completa y cada especie nombrada
+    // esté definida, que cada intervención nombre una enfermedad definida y, si indica especie, una
+    // que sea huésped, y que cada parámetro tenga un valor posible.
+    fn validar(&self) -> Result<(), ScenarioError> {
+        let definiciones = self.definiciones_especies();
+        for d in &definiciones {
+            d.validar().map_err(ScenarioError::Invalido)?;
+        }
+        for p in self.predator.iter().chain(&self.depredadores) {
+            p.validar().map_err(ScenarioError::Invalido)?;
+        }
+        for enfermedad in &self.enfermedades {
+            enfermedad.validar().map_err(ScenarioError::Invalido)?;
+        }
+        if let Some(pastura) = &self.pastura {
+            pastura.validar().map_err(ScenarioError::Invalido)?;
+        }
+
+        let mut nombradas: Vec<(&Species, &str)> = Vec::new();
+        nombradas.extend(self.poblacion.iter().map(|g| (&g.species, "poblacion")));
+        nombradas.extend(self.reproduccion.keys().map(|s| (s, "reproduccion")));
+        nombradas.extend(self.consumo.keys().map(|s| (s, "consumo")));
+        nombradas.extend(self.crecimiento.keys().map(|s| (s, "crecimiento")));
+        nombradas.extend(self.apareamiento.capacidad_por_temporada.keys().map(|s| (s, "apareamiento")));
+        for enfermedad in &self.enfermedades {
+            nombradas.extend(enfermedad.huespedes.keys().map(|s| (s, "enfermedades")));
+        }
+        nombradas.extend(self.intervenciones.iter().filter_map(|i| i.species()).map(|s| (s, "intervenciones")));
+        for p in self.predator.iter().chain(&self.depredadores) {
+            nombradas.extend(p.sacrificio.keys().map(|s| (s, "sacrificio")));
+            nombradas.extend(p.escape.keys().map(|s| (s, "escape")));
+            nombradas.extend(p.estrategia.especies().into_iter().map(|s| (s, "estrategia")));
+        }
+        if let Some((species, seccion)) =
+            nombradas.into_iter().find(|(s, _)| !definiciones.iter().any(|d| d.clave == s.clave()))
+        {
+            return Err(ScenarioError::Invalido(format!(
+                "{} nombra la especie \"{}\", que no está definida",
+                seccion,
+                species.clave()
+            )));
+        }
+
+        for intervencion in &self.intervenciones {
+            intervencion.validar().map_err(ScenarioError::Invalido)?;
+            let nombre = intervencion.enfermedad();
+            let Some(enfermedad) = self.enfermedades.iter().find(|e| e.nombre == nombre) else {
+                return Err(ScenarioError::Invalido(format!(
+                    "la intervención usa la enfermedad \"{}\", que no está en [[enfermedades]]",
+                    nombre
+                )));
+            };
+            if let Some(species) = intervencion.species()
+                && enfermedad.huesped(species).is_none()
+            {
+                return Err(ScenarioError::Invalido(format!(
+                    "{} no es huésped de {}",
+                    species.clave(),
+                    nombre
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    // Ajusta una sola vez las curvas que se ajustan a pesajes y las deja en el escenario como curvas
+    // comunes; después ni la simulación ni el registro vuelven a ajustar nada.
+    fn ajustar_curvas(&mut self) {
+        let nombres: BTreeMap<Species, String> =
+            self.definiciones_especies().iter().map(|d| (d.species(), d.nombre.clone())).collect();
+        let mut ajustadas = Vec::new();
+        for d in &mut self.especies {
+            for (sexo, modelo) in d.crecimiento.ajustar_pesajes() {
+                ajustadas.push((format!("{}{}", d.nombre, sexo), modelo));
+            }
+        }
+        for (species, c) in &mut self.crecimiento {
+            let nombre = nombres.get(species).map_or(species.clave(), |n| n.as_str());
+            for (sexo, modelo) in c.ajustar_pesajes() {
+                ajustadas.push((format!("{}{}", nombre, sexo), modelo));
+            }
+        }
+        self.curvas_ajustadas = ajustadas;
+    }
+
+    pub fn por_defecto() -> Self {
+        Self::from_toml_str(ESCENARIO_POR_DEFECTO).expect("escenarios/default.toml es válido")
+    }
+
+    pub fn crear_simulacion(&self, seed: u64) -> Simulation {
+        let especies = RegistroEspecies::new(&self.definiciones_especies());
+        let mut animals: Vec<Box<dyn Organismo>> = Vec::new();
+        let mut id = 1;
+        for grupo in &self.poblacion {
+            for _ in 0..grupo.cantidad {
+                let especie = especies.get(&grupo.species).expect("especie validada");
+                let mut prey = Prey::new(id, especie, grupo.sex);
+                prey.grupo_contacto = grupo.grupo_contacto;
+                animals.push(Box::new(prey));
+                id += 1;
+            }
+        }
+
+        let mut predators: Vec<Predator> = Vec::new();
+        for p in self.predator.iter().chain(&self.depredadores) {
+            for _ in 0..p.cantidad {
+                let mut predator = Predator::new(p.min_reserve, p.opt_reserve, p.sacrifice_age_days, p.initial_reserve);
+                predator.id = id;
+                predator.especie = p.especie.clone();
+                predator.max_dias_hambre = p.max_dias_hambre;
+                predator.edad_maxima = p.edad_maxima;
+                predator.cria = p.cria.clone();
+                predator.al_extinguirse = p.al_extinguirse;
+                predator.estrategia = p.estrategia.crear();
+                predator.max_cazas_por_dia = p.max_cazas_por_dia;
+                predator.max_intentos_por_dia = p.max_intentos_por_dia;
+                predator.prob_exito = p.prob_exito;
+                predator.sacrificio = p.sacrificio.clone();
+                predator.escape = p.escape.clone();
+                predator.costo_fallo = p.costo_fallo;
+                predator.capacidad_reserva = p.capacidad_reserva;
+                predator.deterioro_diario = p.deterioro_diario;
+                predator.masa_corporal = p.masa_corporal;
+                predator.tasa_metabolica = p.tasa_metabolica;
+                predator.costo_por_intento = p.costo_por_intento;
+                predators.push(predator);
+                id += 1;
+            }
+        }
+
+        let mut sim = Simulation::new(animals, predators, seed);
+        sim.set_especies(especies);
+        sim.set_apareamiento(self.apareamiento.clone());
+        sim.set_enfermedades(RegistroEnfermedades::new(self.enfermedades.clone()));
+        sim.set_plan_sanitario(PlanSanitario::new(self.intervenciones.clone()));
+        if let Some(pastura) = &self.pastura {
+            sim.set_pastura(Pastura::new(pastura.clone()));
+        }
+        sim
+    }
+}
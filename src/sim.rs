@@ -1,25 +1,107 @@
 use crate::traits::Organismo;
-use crate::organism::predator::Predator;
-use rand::rngs::ThreadRng;
+use crate::disease::{Contactos, Progreso, RegistroEnfermedades};
+use crate::events::{Evento, Suscriptor};
+use crate::genealogy::Genealogia;
+use crate::history::{DayRecord, Especies};
+use crate::mating::ConfigApareamiento;
+use crate::organism::predator::{AlExtinguirse, Predator};
+use crate::pasture::Pastura;
+use crate::species::RegistroEspecies;
+use crate::veterinary::PlanSanitario;
+use rand::SeedableRng;
+use rand::seq::SliceRandom;
+use rand_chacha::ChaCha8Rng;
 use std::boxed::Box;
 
 pub struct Simulation {
     day: u32,
     animals: Vec<Box<dyn Organismo>>,
-    predator: Predator,
+    predators: Vec<Predator>,
+    especies: RegistroEspecies,
+    especies_depredador: Vec<EspecieDepredador>,
+    ids: GeneradorIds,
+    seed: u64,
+    rng: ChaCha8Rng,
+    history: Vec<DayRecord>,
+    eventos: Vec<Evento>,
+    suscriptores: Vec<Box<dyn Suscriptor>>,
+    genealogia: Genealogia,
+    apareamiento: ConfigApareamiento,
+    enfermedades: RegistroEnfermedades,
+    plan_sanitario: PlanSanitario,
+    pastura: Option<Pastura>,
+    terminada: bool,
+}
+
+// Una especie de depredador de la corrida: el individuo con el que se la repone y el día en que se
+// extinguió, si se extinguió.
+struct EspecieDepredador {
+    plantilla: Predator,
+    dia_extincion: Option<u32>,
+}
+
+// Reparte ids que no se repiten en toda la corrida, empezando después del mayor id inicial.
+#[derive(Debug, Clone)]
+pub struct GeneradorIds {
     next_id: u32,
 }
 
+impl GeneradorIds {
+    pub fn new(next_id: u32) -> Self {
+        GeneradorIds { next_id }
+    }
+
+    pub fn siguiente(&mut self) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+}
+
 impl Simulation {
-    pub fn new(initial: Vec<Box<dyn Organismo>>, predator: Predator) -> Self {
+    // Misma semilla y mismos parámetros => misma historia, día por día.
+    pub fn new(initial: Vec<Box<dyn Organismo>>, predators: Vec<Predator>, seed: u64) -> Self {
+        let mayor_id = initial
+            .iter()
+            .filter_map(|a| a.id())
+            .chain(predators.iter().map(|p| p.id))
+            .max()
+            .unwrap_or(0);
+        let mut especies_depredador: Vec<EspecieDepredador> = Vec::new();
+        for p in &predators {
+            if !especies_depredador.iter().any(|e| e.plantilla.especie == p.especie) {
+                especies_depredador.push(EspecieDepredador { plantilla: p.clone(), dia_extincion: None });
+            }
+        }
+        let mut genealogia = Genealogia::default();
+        for a in &initial {
+            genealogia.registrar(a.as_ref());
+        }
         Simulation {
             day: 0,
             animals: initial,
-            predator,
-            next_id: 1000,
+            predators,
+            especies: RegistroEspecies::por_defecto(),
+            especies_depredador,
+            ids: GeneradorIds::new(mayor_id + 1),
+            seed,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            history: Vec::new(),
+            eventos: Vec::new(),
+            suscriptores: Vec::new(),
+            genealogia,
+            apareamiento: ConfigApareamiento::default(),
+            enfermedades: RegistroEnfermedades::default(),
+            plan_sanitario: PlanSanitario::default(),
+            pastura: None,
+            terminada: false,
         }
     }
 
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
     
     pub fn get_day(&self) -> u32 {
         self.day
@@ -30,73 +112,261 @@ impl Simulation {
         &self.animals
     }
 
+    pub fn get_animal(&self, id: u32) -> Option<&dyn Organismo> {
+        self.animals.iter().find(|a| a.id() == Some(id)).map(|a| a.as_ref())
+    }
+
     
-    pub fn get_predator(&self) -> &Predator {
-        &self.predator
+    pub fn get_predators(&self) -> &[Predator] {
+        &self.predators
+    }
+
+    pub fn get_history(&self) -> &[DayRecord] {
+        &self.history
+    }
+
+    pub fn ultimo_registro(&self) -> Option<&DayRecord> {
+        self.history.last()
+    }
+
+    pub fn suscribir(&mut self, suscriptor: impl Suscriptor + 'static) {
+        self.suscriptores.push(Box::new(suscriptor));
+    }
+
+    // Las especies de presa de la corrida; tienen que incluir las de los animales iniciales.
+    pub fn set_especies(&mut self, especies: RegistroEspecies) {
+        self.especies = especies;
+    }
+
+    pub fn get_especies(&self) -> &RegistroEspecies {
+        &self.especies
+    }
+
+    pub fn set_apareamiento(&mut self, apareamiento: ConfigApareamiento) {
+        self.apareamiento = apareamiento;
+    }
+
+    pub fn set_enfermedades(&mut self, enfermedades: RegistroEnfermedades) {
+        self.enfermedades = enfermedades;
+    }
+
+    pub fn get_enfermedades(&self) -> &RegistroEnfermedades {
+        &self.enfermedades
+    }
+
+    pub fn set_plan_sanitario(&mut self, plan_sanitario: PlanSanitario) {
+        self.plan_sanitario = plan_sanitario;
+    }
+
+    pub fn get_plan_sanitario(&self) -> &PlanSanitario {
+        &self.plan_sanitario
+    }
+
+    // Sin pastura los animales no comen y nunca pasan hambre.
+    pub fn set_pastura(&mut self, pastura: Pastura) {
+        self.pastura = Some(pastura);
+    }
+
+    pub fn get_pastura(&self) -> Option<&Pastura> {
+        self.pastura.as_ref()
+    }
+
+    // Verdadero cuando se extinguió una especie de depredador que pide terminar; los días siguientes
+    // no simulan nada.
+    pub fn terminada(&self) -> bool {
+        self.terminada
+    }
+
+    pub fn get_genealogia(&self) -> &Genealogia {
+        &self.genealogia
+    }
+
+    pub fn get_eventos_del_dia(&self) -> &[Evento] {
+        &self.eventos
     }
 
     pub fn simulate_day(&mut self) {
+        if self.terminada {
+            return;
+        }
         self.day += 1;
-        let mut rng: ThreadRng = rand::thread_rng();
-        println!("\n--- Día {} ---", self.day);
+        let rng = &mut self.rng;
+        let eventos = &mut self.eventos;
+        eventos.clear();
 
-        
-        self.predator.comenzar_dia();
+        for especie in &mut self.especies_depredador {
+            if let (AlExtinguirse::Reemplazar { dias }, Some(dia_extincion)) =
+                (especie.plantilla.al_extinguirse, especie.dia_extincion)
+                && self.day > dia_extincion + dias
+            {
+                let nuevo = especie.plantilla.reemplazo(self.ids.siguiente(), self.day);
+                eventos.push(Evento::PredatorReplaced { predator_id: nuevo.id, species: nuevo.especie.clone() });
+                self.predators.push(nuevo);
+                especie.dia_extincion = None;
+            }
+        }
+        for p in &mut self.predators {
+            p.comenzar_dia();
+            p.envejecer();
+        }
 
-       
+        // Los contagios del día dependen de quiénes estaban infecciosos al amanecer.
+        let enfermedades = &self.enfermedades;
+        let contactos: Vec<Contactos> = enfermedades
+            .iter()
+            .map(|(idx, e)| e.contar(idx, &self.animals))
+            .collect();
         self.animals.retain_mut(|a| {
             a.envejecer();
+            let (Some(prey_id), Some(species)) = (a.id(), a.especie()) else {
+                return a.esta_vivo();
+            };
 
-            if let Some(_pe) = a.prob_enfermar() {
-                if a.tratar_enfermedad(&mut rng) {
-                    println!("Un individuo de {} murió por enfermedad.", a.nombre());
-                    return false;
+            let presiones: Vec<f64> = if a.aislado() {
+                vec![0.0; enfermedades.len()]
+            } else {
+                enfermedades
+                    .iter()
+                    .map(|(idx, e)| e.presion(&contactos[idx], a.as_ref()))
+                    .collect()
+            };
+            for (idx, progreso) in a.avanzar_enfermedades(rng, enfermedades, &presiones) {
+                let disease = enfermedades.get(idx).map(|e| e.nombre.clone()).unwrap_or_default();
+                match progreso {
+                    Progreso::Murio => {
+                        eventos.push(Evento::DiedOfDisease { prey_id, species: species.clone(), disease });
+                        return false;
+                    }
+                    Progreso::Contagiado => eventos.push(Evento::Infected { prey_id, species: species.clone(), disease }),
+                    Progreso::Enfermo => eventos.push(Evento::FellSick { prey_id, species: species.clone(), disease }),
+                    Progreso::Recuperado => eventos.push(Evento::Recovered { prey_id, species: species.clone(), disease }),
+                    Progreso::PerdioInmunidad => eventos.push(Evento::ImmunityWaned { prey_id, species: species.clone(), disease }),
+                    Progreso::SinCambios => {}
                 }
             }
 
-            a.esta_vivo()
+            if a.muere_de_hambre() {
+                eventos.push(Evento::StarvedToDeath { prey_id, species: species.clone(), days_hungry: a.dias_hambre().unwrap_or(0) });
+                return false;
+            }
+            let vivo = a.esta_vivo();
+            if !vivo {
+                eventos.push(Evento::DiedOfOldAge { prey_id, species, age_days: a.edad().unwrap_or(0) });
+            }
+            vivo
         });
 
-        
+        // Vacunas, tratamientos y cuarentenas, antes de los apareamientos para que los aislados no
+        // entren en celo.
+        let costos_veterinarios =
+            self.plan_sanitario.aplicar(self.day, &mut self.animals, enfermedades, rng, eventos);
+
+        // Pastan los que ya estaban vivos al amanecer; las crías de hoy empiezan mañana.
+        let pastoreo = self.pastura.as_mut().map(|p| p.pastorear(&mut self.animals));
+
+        // Partos de las gestaciones que llegaron a término.
         let mut nuevos: Vec<Box<dyn Organismo>> = Vec::new();
-        {
-            let poblacion_snapshot = &self.animals;
-            for a in &self.animals {
-                let hijos = a.reproducirse(&mut rng, poblacion_snapshot);
-                nuevos.extend(hijos);
+        for a in self.animals.iter_mut() {
+            let hijos = a.reproducirse(rng, &mut self.ids, self.day);
+            for hijo in &hijos {
+                self.genealogia.registrar(hijo.as_ref());
+                if let (Some(prey_id), Some(mother_id), Some(species)) = (hijo.id(), hijo.madre(), hijo.especie()) {
+                    eventos.push(Evento::Birth { prey_id, mother_id, father_id: hijo.padre(), species });
+                }
+            }
+            nuevos.extend(hijos);
+        }
+
+        // Cada hembra en celo elige al azar entre los machos maduros de su especie que todavía tienen
+        // capacidad en la temporada, y queda preñada.
+        let temporada = self.apareamiento.temporada(self.day);
+        for i in 0..self.animals.len() {
+            if !self.animals[i].en_celo(rng, self.day) {
+                continue;
             }
+            let (Some(mother_id), Some(species)) = (self.animals[i].id(), self.animals[i].especie()) else {
+                continue;
+            };
+            let capacidad = self
+                .apareamiento
+                .capacidad(&species)
+                .or_else(|| self.especies.get(&species).map(|e| e.capacidad_apareamiento))
+                .unwrap_or(0);
+            let machos: Vec<usize> = self
+                .animals
+                .iter()
+                .enumerate()
+                .filter(|(_, m)| m.especie().as_ref() == Some(&species) && m.puede_aparearse(temporada, capacidad))
+                .map(|(j, _)| j)
+                .collect();
+            let Some(&j) = machos.choose(rng) else {
+                eventos.push(Evento::MateNotFound { prey_id: mother_id, species });
+                continue;
+            };
+            self.animals[j].registrar_apareamiento(temporada);
+            let Some(father_id) = self.animals[j].id() else {
+                continue;
+            };
+            self.animals[i].concebir(father_id, self.day);
+            eventos.push(Evento::Mated { female_id: mother_id, male_id: father_id, species });
         }
         self.animals.extend(nuevos);
 
-        
-        self.predator.cazar(&mut self.animals, &mut rng);
-
-        
-        self.predator.chequear_al_final_del_dia();
-        if !self.predator.esta_vivo() {
-            println!(
-                "El depredador enfermó/murió por no alcanzar el mínimo diario ({:.2} < {:.2}).",
-                self.predator.consumido_hoy,
-                self.predator.min_reserve
-            );
+        // Cazan por rondas: en cada una, los que siguen con hambre hacen un intento, en un orden al azar
+        // para que ninguno tenga siempre la primera elección.
+        let mut cazando: Vec<usize> = (0..self.predators.len()).collect();
+        while !cazando.is_empty() {
+            cazando.shuffle(rng);
+            cazando.retain(|&i| self.predators[i].cazar(&mut self.animals, rng, eventos));
+        }
+        for p in &mut self.predators {
+            p.chequear_al_final_del_dia(eventos);
         }
 
-        
-        let mut conteo: std::collections::HashMap<&str, usize> = std::collections::HashMap::new();
-        for a in &self.animals {
-            *conteo.entry(a.nombre()).or_insert(0) += 1;
+        // Crían los que quedaron bien alimentados; los muertos salen de la población.
+        let mut cachorros: Vec<Predator> = Vec::new();
+        for p in &mut self.predators {
+            for cachorro in p.criar(rng, &mut self.ids, self.day) {
+                eventos.push(Evento::PredatorBorn {
+                    predator_id: cachorro.id,
+                    parent_id: p.id,
+                    species: cachorro.especie.clone(),
+                });
+                cachorros.push(cachorro);
+            }
         }
-        println!("Población actual: {} animales", self.animals.len());
-        for (nombre, cantidad) in conteo {
-            println!("{}: {}", nombre, cantidad);
+        self.predators.retain(|p| p.vivo);
+        self.predators.extend(cachorros);
+
+        for especie in &mut self.especies_depredador {
+            let quedan = self.predators.iter().any(|p| p.especie == especie.plantilla.especie);
+            if !quedan && especie.dia_extincion.is_none() {
+                especie.dia_extincion = Some(self.day);
+                if especie.plantilla.al_extinguirse == AlExtinguirse::Terminar {
+                    self.terminada = true;
+                }
+            }
         }
 
-       
-        println!(
-            "Reserva acumulada del depredador: {:.2}, consumido hoy: {:.2}, estado: {}",
-            self.predator.current_reserve(),
-            self.predator.consumido_hoy,
-            if self.predator.esta_vivo() { "vivo" } else { "enfermo/muerto" }
+        self.genealogia.procesar_eventos(self.day, &self.eventos);
+        let especies_depredador: Vec<String> =
+            self.especies_depredador.iter().map(|e| e.plantilla.especie.clone()).collect();
+        let especies_presa = self.especies.species();
+        let mut registro = DayRecord::tomar(
+            self.day,
+            &self.animals,
+            &self.predators,
+            Especies { presas: &especies_presa, depredadores: &especies_depredador },
+            &self.enfermedades,
+            costos_veterinarios,
+            &self.eventos,
         );
+        if let Some(pastoreo) = &pastoreo {
+            registro.agregar_pastoreo(pastoreo);
+        }
+        for suscriptor in &mut self.suscriptores {
+            suscriptor.dia_simulado(&registro, &self.eventos);
+        }
+        self.history.push(registro);
     }
 }
@@ -0,0 +1,210 @@
+use crate::growth::{CrecimientoEspecie, CurvasCrecimiento};
+use crate::pasture::ConsumoForraje;
+use crate::reproduction::ParametrosReproductivos;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+const ESPECIES_POR_DEFECTO: &str = include_str!("../escenarios/especies.toml");
+
+// Una especie de presa. Se la identifica por su clave ("Cow"), que es como se la nombra en los
+// escenarios; el nombre ("Vaca") es el que se muestra. Copiarla es barato.
+#[derive(Clone)]
+pub struct Species {
+    clave: Rc<str>,
+    nombre: Rc<str>,
+}
+
+impl Species {
+    pub fn new(clave: &str, nombre: &str) -> Self {
+        Species { clave: Rc::from(clave), nombre: Rc::from(nombre) }
+    }
+
+    pub fn clave(&self) -> &str {
+        &self.clave
+    }
+
+    pub fn nombre(&self) -> &str {
+        &self.nombre
+    }
+}
+
+// Leída de un escenario solo se conoce la clave; alcanza para buscarla en el registro.
+impl From<String> for Species {
+    fn from(clave: String) -> Self {
+        Species::new(&clave, &clave)
+    }
+}
+
+impl Serialize for Species {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.clave)
+    }
+}
+
+impl<'de> Deserialize<'de> for Species {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Species::from)
+    }
+}
+
+impl PartialEq for Species {
+    fn eq(&self, other: &Self) -> bool {
+        self.clave == other.clave
+    }
+}
+
+impl Eq for Species {}
+
+impl PartialOrd for Species {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Species {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.clave.cmp(&other.clave)
+    }
+}
+
+impl Hash for Species {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.clave.hash(state);
+    }
+}
+
+impl fmt::Debug for Species {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.clave)
+    }
+}
+
+// Todo lo que hace falta para simular una especie de presa, tal como se escribe en la configuración.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DefinicionEspecie {
+    pub clave: String,
+    pub nombre: String,
+    // RGB para el visor.
+    #[serde(default = "color_por_defecto")]
+    pub color: [u8; 3],
+    // Nadie, enfermo o sano, pasa de esta edad en días.
+    pub edad_maxima: u32,
+    pub crecimiento: CrecimientoEspecie,
+    pub reproduccion: ParametrosReproductivos,
+    // Hembras que puede cubrir cada macho por temporada, salvo que `[apareamiento]` diga otra cosa.
+    #[serde(default = "capacidad_apareamiento_por_defecto")]
+    pub capacidad_apareamiento: u32,
+    pub consumo: ConsumoForraje,
+    // Multiplica el riesgo de contagiarse de cualquier enfermedad de la que sea huésped.
+    #[serde(default = "susceptibilidad_por_defecto")]
+    pub susceptibilidad: f64,
+}
+
+fn color_por_defecto() -> [u8; 3] {
+    [80, 80, 80]
+}
+
+fn capacidad_apareamiento_por_defecto() -> u32 {
+    40
+}
+
+fn susceptibilidad_por_defecto() -> f64 {
+    1.0
+}
+
+impl DefinicionEspecie {
+    pub fn species(&self) -> Species {
+        Species::new(&self.clave, &self.nombre)
+    }
+
+    pub fn validar(&self) -> Result<(), String> {
+        self.reproduccion.validar().map_err(|motivo| format!("reproducción de {}: {}", self.nombre, motivo))?;
+        self.consumo.validar().map_err(|motivo| format!("consumo de {}: {}", self.nombre, motivo))?;
+        if !self.crecimiento.completo() {
+            return Err(format!("{} no tiene curva de crecimiento para los dos sexos", self.nombre));
+        }
+        for modelo in self.crecimiento.modelos() {
+            modelo.validar().map_err(|motivo| format!("crecimiento de {}: {}", self.nombre, motivo))?;
+        }
+        Ok(())
+    }
+
+    // Las curvas ajustadas a pesajes se ajustan acá, una sola vez.
+    pub fn crear(&self) -> Especie {
+        Especie {
+            species: self.species(),
+            color: self.color,
+            edad_maxima: self.edad_maxima,
+            curvas: self.crecimiento.curvas().expect("curva de crecimiento para los dos sexos"),
+            reproduccion: self.reproduccion.clone(),
+            capacidad_apareamiento: self.capacidad_apareamiento,
+            consumo: self.consumo.clone(),
+            susceptibilidad: self.susceptibilidad,
+        }
+    }
+}
+
+// Una especie lista para crear individuos.
+#[derive(Debug, Clone)]
+pub struct Especie {
+    pub species: Species,
+    pub color: [u8; 3],
+    pub edad_maxima: u32,
+    pub curvas: CurvasCrecimiento,
+    pub reproduccion: ParametrosReproductivos,
+    pub capacidad_apareamiento: u32,
+    pub consumo: ConsumoForraje,
+    pub susceptibilidad: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ArchivoEspecies {
+    especies: Vec<DefinicionEspecie>,
+}
+
+// Las especies de fábrica, de escenarios/especies.toml.
+pub fn definiciones_por_defecto() -> Vec<DefinicionEspecie> {
+    let archivo: ArchivoEspecies = toml::from_str(ESPECIES_POR_DEFECTO).expect("escenarios/especies.toml es válido");
+    archivo.especies
+}
+
+// Las especies de presa de una corrida, en el orden de sus columnas en el CSV.
+#[derive(Debug, Clone)]
+pub struct RegistroEspecies {
+    especies: Vec<Rc<Especie>>,
+}
+
+impl RegistroEspecies {
+    pub fn new(definiciones: &[DefinicionEspecie]) -> Self {
+        RegistroEspecies { especies: definiciones.iter().map(|d| Rc::new(d.crear())).collect() }
+    }
+
+    pub fn por_defecto() -> Self {
+        Self::new(&definiciones_por_defecto())
+    }
+
+    pub fn get(&self, species: &Species) -> Option<&Rc<Especie>> {
+        self.especies.iter().find(|e| &e.species == species)
+    }
+
+    pub fn buscar(&self, clave: &str) -> Option<&Rc<Especie>> {
+        self.especies.iter().find(|e| e.species.clave() == clave)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Rc<Especie>> {
+        self.especies.iter()
+    }
+
+    pub fn species(&self) -> Vec<Species> {
+        self.especies.iter().map(|e| e.species.clone()).collect()
+    }
+}
+
+impl Default for RegistroEspecies {
+    fn default() -> Self {
+        Self::por_defecto()
+    }
+}
@@ -1,10 +1,22 @@
-use crate::organism::prey::Species;
-use rand::rngs::ThreadRng;
+use crate::disease::{EstadoSalud, Progreso, RegistroEnfermedades};
+use crate::species::Species;
+use crate::reproduction::Gestacion;
+use crate::sim::GeneradorIds;
+use rand::RngCore;
 
 pub trait Organismo {
     fn envejecer(&mut self);
-    fn reproducirse(&self, rng: &mut ThreadRng, poblacion: &Vec<Box<dyn Organismo>>)
-        -> Vec<Box<dyn Organismo>>;
+    fn en_celo(&self, rng: &mut dyn RngCore, dia: u32) -> bool;
+    fn puede_aparearse(&self, temporada: u32, capacidad: u32) -> bool;
+    fn registrar_apareamiento(&mut self, temporada: u32);
+    fn concebir(&mut self, padre: u32, dia: u32);
+    fn gestacion(&self) -> Option<Gestacion>;
+    fn reproducirse(
+        &mut self,
+        rng: &mut dyn RngCore,
+        ids: &mut GeneradorIds,
+        dia: u32,
+    ) -> Vec<Box<dyn Organismo>>;
     fn peso(&self) -> f64;
     fn esta_vivo(&self) -> bool;
     fn nombre(&self) -> &str;
@@ -12,10 +24,35 @@ pub trait Organismo {
     fn especie(&self) -> Option<Species>;
     fn es_macho(&self) -> Option<bool>;
 
-    fn prob_enfermar(&self) -> Option<f64>;
-    fn prob_muerte_enfermedad(&self) -> Option<f64>;
+    // `presiones[i]` es la presión de contagio de la enfermedad `i` del registro.
+    fn avanzar_enfermedades(
+        &mut self,
+        rng: &mut dyn RngCore,
+        enfermedades: &RegistroEnfermedades,
+        presiones: &[f64],
+    ) -> Vec<(usize, Progreso)>;
+    fn salud(&self, enfermedad: usize) -> Option<EstadoSalud>;
+    fn set_salud(&mut self, enfermedad: usize, estado: EstadoSalud);
+    fn contagia(&self, enfermedad: usize) -> bool;
+    // Enfermo de cualquier enfermedad.
+    fn esta_enfermo(&self) -> bool;
+    // Un animal aislado en cuarentena no contagia, no se contagia y no se reproduce.
+    fn aislado(&self) -> bool;
+    fn set_aislado(&mut self, aislado: bool);
+    fn grupo_contacto(&self) -> Option<u32>;
 
-    fn tratar_enfermedad(&mut self, rng: &mut ThreadRng) -> bool;
+    // Kilos de pasto que pide hoy; `None` si no pastorea.
+    fn demanda_forraje(&self) -> Option<f64>;
+    fn comer(&mut self, kg: f64);
+    // Días seguidos comiendo menos de lo que necesita para sostener el peso.
+    fn dias_hambre(&self) -> Option<u32>;
+    fn muere_de_hambre(&self) -> bool;
+    // Peso sobre el mayor peso que alcanzó.
+    fn condicion(&self) -> Option<f64>;
 
     fn edad(&self) -> Option<u32>;
+    fn id(&self) -> Option<u32>;
+    fn madre(&self) -> Option<u32>;
+    fn padre(&self) -> Option<u32>;
+    fn dia_nacimiento(&self) -> Option<u32>;
 }
@@ -1,12 +1,15 @@
 use rand::Rng;
 
-pub fn crecimiento_gompertz(a: f64, b: f64, k: f64) -> impl Fn(u32) -> f64 {
-    move |edad: u32| {
-        let t = edad as f64;
-        a * (-b * (-k * t).exp()).exp()
-    }
+pub fn probabilidad(p: f64, rng: &mut (impl Rng + ?Sized)) -> bool {
+    rng.gen_bool(p)
 }
 
-pub fn probabilidad(p: f64, rng: &mut impl Rng) -> bool {
-    rng.gen_bool(p)
+// Al cargar un escenario: un valor fuera de rango se rechaza ahí y no rompe la corrida a mitad de
+// camino, como `gen_bool` con una probabilidad mayor que 1.
+pub fn validar_probabilidad(nombre: &str, p: f64) -> Result<(), String> {
+    if (0.0..=1.0).contains(&p) { Ok(()) } else { Err(format!("{} debe estar entre 0 y 1 (vale {})", nombre, p)) }
+}
+
+pub fn validar_no_negativo(nombre: &str, valor: f64) -> Result<(), String> {
+    if valor >= 0.0 { Ok(()) } else { Err(format!("{} no puede ser negativo (vale {})", nombre, valor)) }
 }
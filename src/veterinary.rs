@@ -0,0 +1,235 @@
+use crate::disease::{EstadoSalud, RegistroEnfermedades};
+use crate::events::Evento;
+use crate::species::Species;
+use crate::traits::Organismo;
+use crate::util::{probabilidad, validar_no_negativo, validar_probabilidad};
+use rand::RngCore;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+// Vacuna una fracción de la especie el día `dia` y, si se indica, cada `repetir_cada` días después.
+// La dosis se paga siempre, pero solo protege a los susceptibles.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Vacunacion {
+    pub enfermedad: String,
+    pub species: Species,
+    pub dia: u32,
+    #[serde(default)]
+    pub repetir_cada: Option<u32>,
+    pub fraccion: f64,
+    #[serde(default)]
+    pub costo_por_dosis: f64,
+    // Omitirlo significa protección de por vida.
+    #[serde(default)]
+    pub dias_proteccion: Option<u32>,
+}
+
+// Cada día de la ventana se trata a todos los enfermos; el costo es por animal tratado y por día.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Tratamiento {
+    pub enfermedad: String,
+    // Sin especie se trata a todas las especies huésped.
+    #[serde(default)]
+    pub species: Option<Species>,
+    #[serde(default)]
+    pub desde: u32,
+    #[serde(default)]
+    pub hasta: Option<u32>,
+    pub prob_curacion: f64,
+    #[serde(default)]
+    pub costo_por_dia: f64,
+}
+
+// Los enfermos quedan aislados: no contagian, no se contagian y no se reproducen hasta que dejan
+// de estar enfermos.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Cuarentena {
+    pub enfermedad: String,
+    #[serde(default)]
+    pub desde: u32,
+    #[serde(default)]
+    pub hasta: Option<u32>,
+    #[serde(default)]
+    pub costo_por_dia: f64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "tipo", rename_all = "snake_case")]
+pub enum Intervencion {
+    Vacunacion(Vacunacion),
+    Tratamiento(Tratamiento),
+    Cuarentena(Cuarentena),
+}
+
+impl Intervencion {
+    pub fn enfermedad(&self) -> &str {
+        match self {
+            Intervencion::Vacunacion(v) => &v.enfermedad,
+            Intervencion::Tratamiento(t) => &t.enfermedad,
+            Intervencion::Cuarentena(c) => &c.enfermedad,
+        }
+    }
+
+    pub fn validar(&self) -> Result<(), String> {
+        let campo = |nombre: &str, tipo: &str| format!("{} {} contra {}", nombre, tipo, self.enfermedad());
+        match self {
+            Intervencion::Vacunacion(v) => {
+                validar_probabilidad(&campo("fraccion", "de la vacunación"), v.fraccion)?;
+                validar_no_negativo(&campo("costo_por_dosis", "de la vacunación"), v.costo_por_dosis)
+            }
+            Intervencion::Tratamiento(t) => {
+                validar_probabilidad(&campo("prob_curacion", "del tratamiento"), t.prob_curacion)?;
+                validar_no_negativo(&campo("costo_por_dia", "del tratamiento"), t.costo_por_dia)
+            }
+            Intervencion::Cuarentena(c) => {
+                validar_no_negativo(&campo("costo_por_dia", "de la cuarentena"), c.costo_por_dia)
+            }
+        }
+    }
+
+    pub fn species(&self) -> Option<&Species> {
+        match self {
+            Intervencion::Vacunacion(v) => Some(&v.species),
+            Intervencion::Tratamiento(t) => t.species.as_ref(),
+            Intervencion::Cuarentena(_) => None,
+        }
+    }
+}
+
+impl Vacunacion {
+    fn toca(&self, dia: u32) -> bool {
+        match self.repetir_cada {
+            Some(cada) if cada > 0 => dia >= self.dia && (dia - self.dia).is_multiple_of(cada),
+            _ => dia == self.dia,
+        }
+    }
+}
+
+fn en_ventana(dia: u32, desde: u32, hasta: Option<u32>) -> bool {
+    dia >= desde && hasta.is_none_or(|hasta| dia <= hasta)
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct CostosVeterinarios {
+    pub vacunacion: f64,
+    pub tratamiento: f64,
+    pub cuarentena: f64,
+}
+
+impl CostosVeterinarios {
+    pub fn total(&self) -> f64 {
+        self.vacunacion + self.tratamiento + self.cuarentena
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PlanSanitario {
+    intervenciones: Vec<Intervencion>,
+}
+
+impl PlanSanitario {
+    pub fn new(intervenciones: Vec<Intervencion>) -> Self {
+        PlanSanitario { intervenciones }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervenciones.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Intervencion> {
+        self.intervenciones.iter()
+    }
+
+    // Vacunas del día, después tratamientos y por último cuarentenas, así los curados hoy no
+    // llegan a aislarse.
+    pub fn aplicar(
+        &self,
+        dia: u32,
+        animals: &mut [Box<dyn Organismo>],
+        enfermedades: &RegistroEnfermedades,
+        rng: &mut dyn RngCore,
+        eventos: &mut Vec<Evento>,
+    ) -> CostosVeterinarios {
+        let mut costos = CostosVeterinarios::default();
+
+        for intervencion in &self.intervenciones {
+            let Some(idx) = enfermedades.buscar(intervencion.enfermedad()) else {
+                continue;
+            };
+            let disease = intervencion.enfermedad();
+            match intervencion {
+                Intervencion::Vacunacion(v) if v.toca(dia) => {
+                    let candidatos: Vec<usize> = (0..animals.len())
+                        .filter(|&i| animals[i].especie().as_ref() == Some(&v.species))
+                        .collect();
+                    let dosis = (candidatos.len() as f64 * v.fraccion).round() as usize;
+                    for &i in candidatos.choose_multiple(rng, dosis) {
+                        let a = &mut animals[i];
+                        let protected = a.salud(idx) == Some(EstadoSalud::Susceptible);
+                        if protected {
+                            a.set_salud(idx, EstadoSalud::Inmune { dias_restantes: v.dias_proteccion });
+                        }
+                        costos.vacunacion += v.costo_por_dosis;
+                        if let (Some(prey_id), Some(species)) = (a.id(), a.especie()) {
+                            eventos.push(Evento::Vaccinated { prey_id, species, disease: disease.to_string(), protected });
+                        }
+                    }
+                }
+                Intervencion::Tratamiento(t) if en_ventana(dia, t.desde, t.hasta) => {
+                    for a in animals.iter_mut() {
+                        let (Some(prey_id), Some(species)) = (a.id(), a.especie()) else {
+                            continue;
+                        };
+                        if t.species.as_ref().is_some_and(|s| *s != species) || a.salud(idx) != Some(EstadoSalud::Enfermo) {
+                            continue;
+                        }
+                        costos.tratamiento += t.costo_por_dia;
+                        let cured = probabilidad(t.prob_curacion, rng);
+                        if cured {
+                            let dias_restantes = enfermedades
+                                .get(idx)
+                                .and_then(|e| e.huesped(&species))
+                                .and_then(|h| h.dias_inmunidad);
+                            a.set_salud(idx, EstadoSalud::Inmune { dias_restantes });
+                        }
+                        eventos.push(Evento::Treated { prey_id, species, disease: disease.to_string(), cured });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // Un animal sigue aislado mientras esté enfermo de alguna enfermedad con cuarentena vigente.
+        let cuarentenas: Vec<(usize, &Cuarentena)> = self
+            .intervenciones
+            .iter()
+            .filter_map(|i| match i {
+                Intervencion::Cuarentena(c) if en_ventana(dia, c.desde, c.hasta) => {
+                    enfermedades.buscar(&c.enfermedad).map(|idx| (idx, c))
+                }
+                _ => None,
+            })
+            .collect();
+        for a in animals.iter_mut() {
+            let (Some(prey_id), Some(species)) = (a.id(), a.especie()) else {
+                continue;
+            };
+            let mut debe_aislarse = false;
+            for (idx, c) in &cuarentenas {
+                if a.salud(*idx) == Some(EstadoSalud::Enfermo) {
+                    debe_aislarse = true;
+                    costos.cuarentena += c.costo_por_dia;
+                }
+            }
+            if debe_aislarse && !a.aislado() {
+                a.set_aislado(true);
+                eventos.push(Evento::Isolated { prey_id, species });
+            } else if !debe_aislarse && a.aislado() {
+                a.set_aislado(false);
+                eventos.push(Evento::ReleasedFromQuarantine { prey_id, species });
+            }
+        }
+
+        costos
+    }
+}
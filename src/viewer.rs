@@ -0,0 +1,75 @@
+use macroquad::prelude::*;
+use simulacion::Simulation;
+
+// Una curva por especie del registro: la clave como etiqueta, su color y la población de cada día.
+fn series(sim: &Simulation) -> Vec<(String, Color, Vec<usize>)> {
+    sim.get_especies()
+        .iter()
+        .map(|e| {
+            let [r, g, b] = e.color;
+            let serie = sim.get_history().iter().map(|r| r.total(&e.species)).collect();
+            (e.species.clave().to_string(), Color::from_rgba(r, g, b, 255), serie)
+        })
+        .collect()
+}
+
+fn dibujar(series: &[(String, Color, Vec<usize>)], total_dias: u32) {
+    let graph_x0 = 60.0;
+    let graph_y0 = screen_height() - 60.0;
+    let graph_width = screen_width() - 120.0;
+    let graph_height = screen_height() - 120.0;
+    let max_y = series.iter().flat_map(|(_, _, s)| s.iter()).max().cloned().unwrap_or(1) as f32;
+    let scale_x = graph_width / total_dias as f32;
+    let scale_y = if max_y > 0.0 { graph_height / max_y } else { 1.0 };
+
+    draw_line(graph_x0, graph_y0, graph_x0 + graph_width, graph_y0, 2.0, BLACK);
+    draw_line(graph_x0, graph_y0, graph_x0, graph_y0 - graph_height, 2.0, BLACK);
+
+    for (n, (etiqueta, color, serie)) in series.iter().enumerate() {
+        for (i, &count) in serie.iter().enumerate().skip(1) {
+            let x1 = graph_x0 + (i as f32 - 1.0) * scale_x;
+            let y1 = graph_y0 - (serie[i - 1] as f32) * scale_y;
+            let x2 = graph_x0 + (i as f32) * scale_x;
+            let y2 = graph_y0 - (count as f32) * scale_y;
+            draw_line(x1, y1, x2, y2, 2.0, *color);
+        }
+        let y = graph_y0 - graph_height + 20.0 + 30.0 * n as f32;
+        draw_text(&format!("{}: {}", etiqueta, serie.last().unwrap_or(&0)), graph_x0 + graph_width - 100.0, y, 24.0, *color);
+    }
+}
+
+pub async fn run(mut sim: Simulation, total_dias: u32) {
+    for _ in 0..total_dias {
+        if sim.terminada() {
+            break;
+        }
+        clear_background(LIGHTGRAY);
+        sim.simulate_day();
+
+        dibujar(&series(&sim), total_dias);
+
+        draw_text(
+            &format!(
+                "Día: {}  |  Depredadores: {}  |  Reserva: {:.1}  |  Semilla: {}",
+                sim.get_day(),
+                sim.get_predators().len(),
+                sim.ultimo_registro().map_or(0.0, |r| r.reserva_depredador),
+                sim.get_seed()
+            ),
+            20.0,
+            30.0,
+            24.0,
+            BLACK,
+        );
+
+        next_frame().await;
+    }
+
+    let finales = series(&sim);
+    loop {
+        clear_background(LIGHTGRAY);
+        draw_text("Simulación finalizada", 200.0, 200.0, 40.0, RED);
+        dibujar(&finales, total_dias);
+        next_frame().await;
+    }
+}
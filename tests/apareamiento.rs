@@ -0,0 +1,38 @@
+use simulacion::mating::ConfigApareamiento;
+use simulacion::species::RegistroEspecies;
+use simulacion::{Organismo, Prey, Sex};
+
+fn macho_adulto(especies: &RegistroEspecies) -> Prey {
+    let mut macho = Prey::new(1, especies.buscar("Rabbit").unwrap(), Sex::Male);
+    macho.age_days = macho.especie.reproduccion.edad_madurez;
+    macho
+}
+
+// Cubre a lo sumo `capacidad` hembras por temporada y recupera la cuenta al empezar otra.
+#[test]
+fn un_macho_no_pasa_la_capacidad_de_la_temporada() {
+    let especies = RegistroEspecies::por_defecto();
+    let config = ConfigApareamiento { duracion_temporada: 30, ..Default::default() };
+    let mut macho = macho_adulto(&especies);
+
+    let mut cubiertas = Vec::new();
+    for dia in 1..=90 {
+        let temporada = config.temporada(dia);
+        if macho.puede_aparearse(temporada, 2) {
+            macho.registrar_apareamiento(temporada);
+            cubiertas.push(dia);
+        }
+    }
+    assert_eq!(cubiertas, vec![1, 2, 31, 32, 61, 62]);
+}
+
+// Con capacidad cero no cubre a ninguna, tampoco el primer día de una temporada nueva.
+#[test]
+fn con_capacidad_cero_no_se_aparea() {
+    let especies = RegistroEspecies::por_defecto();
+    let config = ConfigApareamiento { duracion_temporada: 30, ..Default::default() };
+    let mut macho = macho_adulto(&especies);
+    macho.registrar_apareamiento(config.temporada(1));
+
+    assert!((1..=400).all(|dia| !macho.puede_aparearse(config.temporada(dia), 0)));
+}
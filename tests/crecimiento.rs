@@ -0,0 +1,32 @@
+use simulacion::growth::{CurvasCrecimiento, Familia, Gompertz, GrowthModel, ModeloCrecimiento, ajustar};
+use simulacion::species::RegistroEspecies;
+use simulacion::{Organismo, Prey, Sex};
+use std::rc::Rc;
+
+// Pesajes sacados de una Gompertz conocida: el ajuste tiene que devolver los mismos parámetros.
+#[test]
+fn ajustar_recupera_una_gompertz() {
+    let original = Gompertz { a: 700.0, b: 3.0, k: 0.008 };
+    let pesajes: Vec<(u32, f64)> = (0..=20).map(|i| i * 50).map(|edad| (edad, original.peso(edad))).collect();
+
+    let ModeloCrecimiento::Gompertz(ajustada) = ajustar(Familia::Gompertz, &pesajes) else {
+        panic!("el ajuste de una Gompertz tiene que dar una Gompertz");
+    };
+    assert!((ajustada.a - original.a).abs() / original.a < 1e-3, "a = {}", ajustada.a);
+    assert!((ajustada.b - original.b).abs() / original.b < 1e-3, "b = {}", ajustada.b);
+    assert!((ajustada.k - original.k).abs() / original.k < 1e-3, "k = {}", ajustada.k);
+}
+
+// El escenario rechaza una curva así, pero `set_curvas` no pasa por él.
+#[test]
+fn una_curva_negativa_no_rompe_el_crecimiento() {
+    let especies = RegistroEspecies::por_defecto();
+    let mut prey = Prey::new(1, especies.buscar("Rabbit").unwrap(), Sex::Male);
+    let negativa: Rc<dyn GrowthModel> = Rc::new(Gompertz { a: -5.0, b: 2.5, k: 0.05 });
+    prey.set_curvas(CurvasCrecimiento { machos: negativa.clone(), hembras: negativa });
+
+    for _ in 0..10 {
+        prey.envejecer();
+    }
+    assert_eq!(prey.peso(), 0.0);
+}
@@ -0,0 +1,25 @@
+use simulacion::species::RegistroEspecies;
+use simulacion::{Genealogia, Prey, Sex};
+
+fn cria(especies: &RegistroEspecies, id: u32, sex: Sex, madre: Option<u32>, padre: Option<u32>) -> Prey {
+    let mut prey = Prey::new(id, especies.buscar("Rabbit").unwrap(), sex);
+    prey.madre = madre;
+    prey.padre = padre;
+    prey
+}
+
+// Un macho que también es abuelo por el lado de la madre: la nieta cuenta una sola vez.
+#[test]
+fn descendientes_no_repite_la_linea_endogamica() {
+    let especies = RegistroEspecies::por_defecto();
+    let mut genealogia = Genealogia::default();
+    genealogia.registrar(&cria(&especies, 1, Sex::Male, None, None));
+    genealogia.registrar(&cria(&especies, 3, Sex::Female, None, None));
+    genealogia.registrar(&cria(&especies, 2, Sex::Female, Some(3), Some(1)));
+    genealogia.registrar(&cria(&especies, 4, Sex::Female, Some(2), Some(1)));
+
+    let ids: Vec<u32> = genealogia.descendientes(1).iter().map(|f| f.id).collect();
+    assert_eq!(ids, vec![2, 4]);
+    let ids: Vec<u32> = genealogia.descendientes(3).iter().map(|f| f.id).collect();
+    assert_eq!(ids, vec![2, 4]);
+}
@@ -0,0 +1,21 @@
+use simulacion::Scenario;
+use simulacion::history::escribir_csv;
+
+fn csv_de_una_corrida(seed: u64) -> Vec<u8> {
+    let escenario = Scenario::por_defecto();
+    let mut sim = escenario.crear_simulacion(seed);
+    for _ in 0..escenario.dias {
+        sim.simulate_day();
+    }
+    let mut csv = Vec::new();
+    escribir_csv(sim.get_history(), &mut csv).unwrap();
+    csv
+}
+
+// Misma semilla y mismo escenario => la misma historia, byte a byte.
+#[test]
+fn misma_semilla_misma_historia() {
+    let primera = csv_de_una_corrida(42);
+    assert!(!primera.is_empty());
+    assert_eq!(primera, csv_de_una_corrida(42));
+}